@@ -0,0 +1,35 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use jsonrpc_sys::{MaybeBatchedRequests, Request, Response};
+
+const REQUEST: &str = r#"{"jsonrpc":"2.0","method":"eth_getBalance","params":["0x407d73d8a49eeb85d32cf465507dd71d507100c1","latest"],"id":1}"#;
+const NOTIFICATION: &str = r#"{"jsonrpc":"2.0","method":"ping","params":null}"#;
+const RESPONSE: &str = r#"{"jsonrpc":"2.0","result":"0x0234c8a3397aab58","id":1}"#;
+const ERROR: &str =
+    r#"{"jsonrpc":"2.0","error":{"code":-32601,"message":"Method not found"},"id":"1"}"#;
+
+fn parse(c: &mut Criterion) {
+    c.bench_function("request", |b| {
+        b.iter(|| serde_json::from_str::<Request<(&str, &str)>>(REQUEST).unwrap())
+    });
+    c.bench_function("notification", |b| {
+        b.iter(|| serde_json::from_str::<Request<()>>(NOTIFICATION).unwrap())
+    });
+    c.bench_function("response", |b| {
+        b.iter(|| serde_json::from_str::<Response<&str, ()>>(RESPONSE).unwrap())
+    });
+    c.bench_function("error", |b| {
+        b.iter(|| serde_json::from_str::<Response<&str, ()>>(ERROR).unwrap())
+    });
+}
+
+fn batch(c: &mut Criterion) {
+    for size in [1, 2, 4, 16] {
+        let batch = format!("[{}]", vec![REQUEST; size].join(","));
+        c.bench_function(&format!("batch/{size}"), |b| {
+            b.iter(|| serde_json::from_str::<MaybeBatchedRequests<(&str, &str)>>(&batch).unwrap())
+        });
+    }
+}
+
+criterion_group!(benches, parse, batch);
+criterion_main!(benches);
@@ -17,6 +17,18 @@ pub enum MaybeBatchedRequests<'a, P> {
     Batch(Vec<Request<'a, P>>),
 }
 
+impl<'a, P> MaybeBatchedRequests<'a, P> {
+    /// Converts these requests into ones that own their method names and identifiers.
+    pub fn into_owned(self) -> MaybeBatchedRequests<'static, P> {
+        match self {
+            Self::Single(single) => MaybeBatchedRequests::Single(single.into_owned()),
+            Self::Batch(batch) => {
+                MaybeBatchedRequests::Batch(batch.into_iter().map(Request::into_owned).collect())
+            }
+        }
+    }
+}
+
 impl<'a, T> Serialize for MaybeBatchedRequests<'a, T>
 where
     T: Clone + Serialize,
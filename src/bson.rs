@@ -0,0 +1,107 @@
+//! Encoding of JSON-RPC 2.0 messages as BSON documents.
+//!
+//! The data model of BSON differs slightly from JSON's:
+//!
+//! - BSON has no unsigned integers. [`Id::Uint`] values that fit in an `i64` are encoded as
+//!   64-bit integers and read back as [`Id::Int`]; larger ones cannot be encoded.
+//! - Top-level BSON values must be documents, so batches cannot be encoded as-is.
+//!
+//! Optional members (the `id` of notifications, and the `data` of errors) are omitted from
+//! the encoded documents, exactly like in JSON.
+//!
+//! [`Id::Int`]: crate::Id::Int
+//! [`Id::Uint`]: crate::Id::Uint
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Request, Response};
+
+/// Writes a JSON-RPC 2.0 request as a BSON document.
+pub fn write_request_bson<P>(request: &Request<P>) -> ::bson::ser::Result<Vec<u8>>
+where
+    P: Serialize,
+{
+    ::bson::to_vec(request)
+}
+
+/// Writes a JSON-RPC 2.0 response as a BSON document.
+pub fn write_response_bson<T, E>(response: &Response<T, E>) -> ::bson::ser::Result<Vec<u8>>
+where
+    T: Serialize,
+    E: Serialize,
+{
+    ::bson::to_vec(response)
+}
+
+/// Reads a JSON-RPC 2.0 request from a BSON document.
+pub fn read_request_bson<'a, P>(bytes: &'a [u8]) -> ::bson::de::Result<Request<'a, P>>
+where
+    P: Deserialize<'a>,
+{
+    ::bson::from_slice(bytes)
+}
+
+/// Reads a JSON-RPC 2.0 response from a BSON document.
+pub fn read_response_bson<'a, T, E>(bytes: &'a [u8]) -> ::bson::de::Result<Response<'a, T, E>>
+where
+    T: Deserialize<'a>,
+    E: Deserialize<'a>,
+{
+    ::bson::from_slice(bytes)
+}
+
+#[test]
+#[cfg(test)]
+fn roundtrip() {
+    use std::borrow::Cow;
+
+    use crate::{Error, ErrorCode, Id};
+
+    // Notifications have no `id` member at all.
+    let request = Request {
+        method: Cow::Borrowed("notify"),
+        params: [1, 2],
+        id: None,
+    };
+    let bytes = write_request_bson(&request).unwrap();
+    let doc = ::bson::Document::from_reader(&*bytes).unwrap();
+    assert!(!doc.contains_key("id"));
+    let request = read_request_bson::<[i32; 2]>(&bytes).unwrap();
+    assert_eq!(request.id, None);
+    assert_eq!(request.params, [1, 2]);
+
+    // A `null` id is kept as such.
+    let request = Request {
+        method: Cow::Borrowed("call"),
+        params: (),
+        id: Some(Id::Null),
+    };
+    let bytes = write_request_bson(&request).unwrap();
+    assert_eq!(read_request_bson::<()>(&bytes).unwrap().id, Some(Id::Null));
+
+    // Unsigned ids come back as signed ones.
+    let response = Response::<(), ()> {
+        result: Err(Error {
+            code: ErrorCode::METHOD_NOT_FOUND,
+            message: Cow::Borrowed("not found"),
+            data: None,
+        }),
+        id: Id::Uint(7),
+    };
+    let bytes = write_response_bson(&response).unwrap();
+    let doc = ::bson::Document::from_reader(&*bytes).unwrap();
+    assert!(!doc.get_document("error").unwrap().contains_key("data"));
+    let response = read_response_bson::<(), ()>(&bytes).unwrap();
+    assert_eq!(response.id, Id::Int(7));
+    assert_eq!(
+        response.result.unwrap_err().code,
+        ErrorCode::METHOD_NOT_FOUND
+    );
+
+    // Ids that don't fit in an `i64` cannot be encoded.
+    let response = Response::<(), ()> {
+        result: Ok(()),
+        id: Id::Uint(u64::MAX),
+    };
+    assert!(write_response_bson(&response).is_err());
+}
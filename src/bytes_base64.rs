@@ -0,0 +1,121 @@
+//! Serializes byte buffers as base64 strings.
+//!
+//! Bytes are written using the standard alphabet with padding. When reading, padding is
+//! optional, but the URL-safe alphabet is rejected.
+//!
+//! ```
+//! # use serde::{Deserialize, Serialize};
+//! #[derive(Serialize, Deserialize)]
+//! struct Upload {
+//!     #[serde(with = "jsonrpc_sys::bytes_base64")]
+//!     contents: Vec<u8>,
+//!     // Rejects thumbnails larger than 4 KiB before decoding them.
+//!     #[serde(
+//!         serialize_with = "jsonrpc_sys::bytes_base64::serialize",
+//!         deserialize_with = "jsonrpc_sys::bytes_base64::deserialize_max::<4096, _, _>"
+//!     )]
+//!     thumbnail: Vec<u8>,
+//! }
+//! ```
+
+use std::fmt;
+use std::marker::PhantomData;
+
+use base64::alphabet::STANDARD;
+use base64::engine::{DecodePaddingMode, GeneralPurpose, GeneralPurposeConfig};
+use base64::Engine;
+use serde::de::{Error, Visitor};
+use serde::{Deserializer, Serializer};
+
+const ENGINE: GeneralPurpose = GeneralPurpose::new(
+    &STANDARD,
+    GeneralPurposeConfig::new().with_decode_padding_mode(DecodePaddingMode::Indifferent),
+);
+
+/// Serializes the provided bytes as a base64 string.
+pub fn serialize<T, S>(bytes: &T, serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: ?Sized + AsRef<[u8]>,
+    S: Serializer,
+{
+    serializer.serialize_str(&ENGINE.encode(bytes))
+}
+
+/// Deserializes bytes from a base64 string.
+pub fn deserialize<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: From<Vec<u8>>,
+{
+    deserializer.deserialize_str(Base64Visitor(usize::MAX, PhantomData))
+}
+
+/// Deserializes at most `N` bytes from a base64 string.
+///
+/// Strings too long to decode to `N` bytes or fewer are rejected before being decoded.
+pub fn deserialize_max<'de, const N: usize, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: From<Vec<u8>>,
+{
+    deserializer.deserialize_str(Base64Visitor(N, PhantomData))
+}
+
+/// Decodes a base64 string, rejecting it if it holds more than `.0` bytes.
+struct Base64Visitor<T>(usize, PhantomData<T>);
+
+impl<'de, T> Visitor<'de> for Base64Visitor<T>
+where
+    T: From<Vec<u8>>,
+{
+    type Value = T;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a base64 string")
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: Error,
+    {
+        let len = base64::decoded_len_estimate(v.len());
+        if len > self.0.saturating_add(2) {
+            return Err(E::invalid_length(len, &self));
+        }
+
+        let bytes = ENGINE.decode(v).map_err(E::custom)?;
+        if bytes.len() > self.0 {
+            return Err(E::invalid_length(bytes.len(), &self));
+        }
+
+        Ok(T::from(bytes))
+    }
+}
+
+#[test]
+#[cfg(test)]
+fn base64() {
+    #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+    struct Blob(#[serde(with = "self")] Vec<u8>);
+
+    #[derive(Debug, serde::Deserialize)]
+    struct Small(#[serde(deserialize_with = "deserialize_max::<2, _, _>")] Vec<u8>);
+
+    assert_eq!(
+        serde_json::to_string(&Blob(b"abcd".to_vec())).unwrap(),
+        r#""YWJjZA==""#
+    );
+    assert_eq!(
+        serde_json::from_str::<Blob>(r#""YWJjZA==""#).unwrap(),
+        Blob(b"abcd".to_vec())
+    );
+    assert_eq!(
+        serde_json::from_str::<Blob>(r#""YWJjZA""#).unwrap(),
+        Blob(b"abcd".to_vec())
+    );
+    assert!(serde_json::from_str::<Blob>(r#""YW_jZA""#).is_err());
+
+    assert_eq!(serde_json::from_str::<Small>(r#""YWI=""#).unwrap().0, b"ab");
+    assert!(serde_json::from_str::<Small>(r#""YWJj""#).is_err());
+    assert!(serde_json::from_str::<Small>(r#""YWJjZGVmZ2hp""#).is_err());
+}
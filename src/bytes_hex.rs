@@ -0,0 +1,74 @@
+//! Serializes byte buffers as hexadecimal strings.
+//!
+//! Bytes are written as lowercase hexadecimal digits, without any prefix. When reading, both
+//! lowercase and uppercase digits are accepted, but a `0x` prefix is not. Use the
+//! [`hex_data`](crate::hex_data) module for `0x`-prefixed data.
+//!
+//! ```
+//! # use serde::{Deserialize, Serialize};
+//! #[derive(Serialize, Deserialize)]
+//! struct Digest {
+//!     #[serde(with = "jsonrpc_sys::bytes_hex")]
+//!     sha256: Vec<u8>,
+//! }
+//! ```
+
+use serde::{Deserializer, Serializer};
+
+use crate::hex_data::{encode, DataVisitor, Prefix};
+
+/// Serializes the provided bytes as a hexadecimal string.
+pub fn serialize<T, S>(bytes: &T, serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: ?Sized + AsRef<[u8]>,
+    S: Serializer,
+{
+    serializer.serialize_str(&encode(bytes.as_ref(), ""))
+}
+
+/// Deserializes bytes from a hexadecimal string.
+pub fn deserialize<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: TryFrom<Vec<u8>>,
+{
+    deserializer.deserialize_str(DataVisitor::new(Prefix::Forbidden, usize::MAX))
+}
+
+/// Deserializes at most `N` bytes from a hexadecimal string.
+///
+/// Strings too long to decode to `N` bytes or fewer are rejected before being decoded.
+pub fn deserialize_max<'de, const N: usize, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: TryFrom<Vec<u8>>,
+{
+    deserializer.deserialize_str(DataVisitor::new(Prefix::Forbidden, N))
+}
+
+#[test]
+#[cfg(test)]
+fn hex() {
+    #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+    struct Blob(#[serde(with = "self")] Vec<u8>);
+
+    #[derive(Debug, serde::Deserialize)]
+    struct Small(#[serde(deserialize_with = "deserialize_max::<2, _, _>")] Box<[u8]>);
+
+    assert_eq!(
+        serde_json::to_string(&Blob(vec![0xab, 0x01])).unwrap(),
+        r#""ab01""#
+    );
+    assert_eq!(
+        serde_json::from_str::<Blob>(r#""AB01""#).unwrap(),
+        Blob(vec![0xab, 0x01])
+    );
+    assert!(serde_json::from_str::<Blob>(r#""ab0""#).is_err());
+    assert!(serde_json::from_str::<Blob>(r#""0xab""#).is_err());
+
+    assert_eq!(
+        &*serde_json::from_str::<Small>(r#""abcd""#).unwrap().0,
+        [0xab, 0xcd]
+    );
+    assert!(serde_json::from_str::<Small>(r#""abcdef""#).is_err());
+}
@@ -0,0 +1,390 @@
+//! Deterministic serialization of JSON-RPC 2.0 messages.
+//!
+//! The envelope types of this crate always serialize their members in the same order, but the
+//! parameters, results and error data they carry are arbitrary user types, which may iterate
+//! over hash maps or produce equivalent numbers in different forms. The functions of this
+//! module write messages in a canonical form suitable for snapshot tests and content-addressed
+//! caches:
+//!
+//! - no insignificant whitespace is emitted;
+//! - object members are sorted by the UTF-8 bytes of their keys, at every depth;
+//! - numbers without a fractional part are written as integers (`1.0` becomes `1`, `-0.0`
+//!   becomes `0`), and other numbers use the shortest representation that round-trips;
+//! - non-finite floating point numbers, including in [`Id::Float`], are written as `null`.
+//!
+//! [`Id::Float`]: crate::Id::Float
+
+use serde::Serialize;
+use serde_json::{Number, Value};
+
+use crate::{Id, Request, Response};
+
+/// Serializes the provided value to a vector of bytes, in canonical form.
+pub fn to_canonical_vec<T>(value: &T) -> serde_json::Result<Vec<u8>>
+where
+    T: ?Sized + Serialize,
+{
+    let mut buf = Vec::new();
+    write_canonical(&mut buf, value)?;
+    Ok(buf)
+}
+
+/// Writes a JSON-RPC 2.0 request to a vector of bytes, in canonical form.
+pub fn write_canonical_request<P>(request: &Request<P>) -> serde_json::Result<Vec<u8>>
+where
+    P: Serialize,
+{
+    // The members of the envelope are written by hand, already sorted, so that identifiers
+    // that do not fit in a `serde_json::Value` can be written.
+    let mut buf = Vec::new();
+    buf.push(b'{');
+    if let Some(ref id) = request.id {
+        buf.extend_from_slice(br#""id":"#);
+        write_id(&mut buf, id)?;
+        buf.push(b',');
+    }
+    buf.extend_from_slice(br#""jsonrpc":"2.0","method":"#);
+    serde_json::to_writer(&mut buf, &*request.method)?;
+    buf.extend_from_slice(br#","params":"#);
+    write_canonical(&mut buf, &request.params)?;
+    buf.push(b'}');
+    Ok(buf)
+}
+
+/// Writes a JSON-RPC 2.0 response to a vector of bytes, in canonical form.
+pub fn write_canonical_response<T, E>(response: &Response<T, E>) -> serde_json::Result<Vec<u8>>
+where
+    T: Serialize,
+    E: Serialize,
+{
+    // See `write_canonical_request`.
+    let mut buf = Vec::new();
+    buf.push(b'{');
+    if let Err(ref error) = response.result {
+        buf.extend_from_slice(br#""error":{"code":"#);
+        serde_json::to_writer(&mut buf, &error.code.0)?;
+        if let Some(ref data) = error.data {
+            buf.extend_from_slice(br#","data":"#);
+            write_canonical(&mut buf, data)?;
+        }
+        buf.extend_from_slice(br#","message":"#);
+        serde_json::to_writer(&mut buf, &*error.message)?;
+        buf.extend_from_slice(b"},");
+    }
+    buf.extend_from_slice(br#""id":"#);
+    write_id(&mut buf, &response.id)?;
+    buf.extend_from_slice(br#","jsonrpc":"2.0""#);
+    if let Ok(ref result) = response.result {
+        buf.extend_from_slice(br#","result":"#);
+        write_canonical(&mut buf, result)?;
+    }
+    buf.push(b'}');
+    Ok(buf)
+}
+
+/// Computes a SHA-256 hash of the method and parameters of a request.
+///
+/// The hash is computed over the canonical encoding of the `[method, params]` pair, meaning
+/// that requests that only differ by the order of the keys in their parameters, or by the way
+/// their numbers are written, produce the same hash. The identifier of the request is not
+/// included, making this hash suitable for cache keys, deduplication keys and idempotency
+/// tokens.
+#[cfg(feature = "hash")]
+pub fn canonical_hash<P>(request: &Request<P>) -> serde_json::Result<[u8; 32]>
+where
+    P: Serialize,
+{
+    use sha2::{Digest, Sha256};
+
+    let bytes = to_canonical_vec(&(&*request.method, &request.params))?;
+    Ok(Sha256::digest(bytes).into())
+}
+
+impl<'a, P> Request<'a, P>
+where
+    P: Serialize,
+{
+    /// Returns whether this request is semantically equal to another one.
+    ///
+    /// Two requests are semantically equal when they have the same method, and when their
+    /// identifiers and parameters have the same canonical encoding (see the documentation of
+    /// [`to_canonical_vec`]). In particular, the order of the keys of objects and the way
+    /// numbers are written are ignored.
+    ///
+    /// Requests whose parameters cannot be serialized are never equal.
+    pub fn semantically_eq<Q>(&self, other: &Request<Q>) -> bool
+    where
+        Q: Serialize,
+    {
+        fn canonical_parts<P: Serialize>(r: &Request<P>) -> serde_json::Result<Vec<u8>> {
+            let mut buf = Vec::new();
+            if let Some(ref id) = r.id {
+                write_id(&mut buf, id)?;
+            }
+            buf.push(b',');
+            write_canonical(&mut buf, &r.params)?;
+            Ok(buf)
+        }
+
+        self.method == other.method
+            && matches!(
+                (canonical_parts(self), canonical_parts(other)),
+                (Ok(a), Ok(b)) if a == b
+            )
+    }
+
+    /// Returns a normalized copy of this request, with its parameters converted to a JSON value.
+    ///
+    /// Numbers without a fractional part are converted to integers (in the parameters as well as
+    /// in the identifier), and the keys of objects are sorted. Two requests are
+    /// [semantically equal] if and only if their normalized versions are equal.
+    ///
+    /// [semantically equal]: Request::semantically_eq
+    pub fn normalize(&self) -> serde_json::Result<Request<'a, Value>> {
+        Ok(Request {
+            method: self.method.clone(),
+            params: normalize_value(serde_json::to_value(&self.params)?),
+            id: self.id.as_ref().map(normalize_id),
+        })
+    }
+}
+
+/// Returns the normalized version of a JSON value.
+pub(crate) fn normalize_value(value: Value) -> Value {
+    match value {
+        Value::Number(n) => Value::Number(normalize_number(&n)),
+        Value::Array(array) => Value::Array(array.into_iter().map(normalize_value).collect()),
+        Value::Object(object) => {
+            let mut members: Vec<_> = object.into_iter().collect();
+            members.sort_unstable_by(|a, b| a.0.as_bytes().cmp(b.0.as_bytes()));
+            Value::Object(
+                members
+                    .into_iter()
+                    .map(|(key, value)| (key, normalize_value(value)))
+                    .collect(),
+            )
+        }
+        other => other,
+    }
+}
+
+/// Returns the normalized version of an [`Id`].
+fn normalize_id<'a>(id: &Id<'a>) -> Id<'a> {
+    match *id {
+        Id::Int128(i) => return i64::try_from(i).map_or(Id::Int128(i), Id::Int),
+        Id::Uint128(u) => return u64::try_from(u).map_or(Id::Uint128(u), Id::Uint),
+        _ => (),
+    }
+
+    match serde_json::to_value(id).map(normalize_value) {
+        Ok(Value::Number(n)) if n.is_u64() => Id::Uint(n.as_u64().unwrap()),
+        Ok(Value::Number(n)) if n.is_i64() => Id::Int(n.as_i64().unwrap()),
+        _ => id.clone(),
+    }
+}
+
+/// Writes the provided value to the provided buffer, in canonical form.
+fn write_canonical<T>(buf: &mut Vec<u8>, value: &T) -> serde_json::Result<()>
+where
+    T: ?Sized + Serialize,
+{
+    write_value(buf, &serde_json::to_value(value)?)
+}
+
+/// Writes an [`Id`] to the provided buffer, in canonical form.
+///
+/// 128-bit integers are written directly, as they cannot be represented by a [`Value`] unless
+/// the `arbitrary_precision` feature of `serde_json` is enabled.
+fn write_id(buf: &mut Vec<u8>, id: &Id) -> serde_json::Result<()> {
+    match *id {
+        Id::Int128(i) => buf.extend_from_slice(i.to_string().as_bytes()),
+        Id::Uint128(u) => buf.extend_from_slice(u.to_string().as_bytes()),
+        ref id => write_canonical(buf, id)?,
+    }
+    Ok(())
+}
+
+/// Writes a JSON value to the provided buffer, in canonical form.
+fn write_value(buf: &mut Vec<u8>, value: &Value) -> serde_json::Result<()> {
+    match *value {
+        Value::Null => buf.extend_from_slice(b"null"),
+        Value::Bool(b) => buf.extend_from_slice(if b { b"true" } else { b"false" }),
+        Value::Number(ref n) => serde_json::to_writer(&mut *buf, &normalize_number(n))?,
+        Value::String(ref s) => serde_json::to_writer(&mut *buf, s)?,
+        Value::Array(ref array) => {
+            buf.push(b'[');
+            for (i, item) in array.iter().enumerate() {
+                if i != 0 {
+                    buf.push(b',');
+                }
+                write_value(buf, item)?;
+            }
+            buf.push(b']');
+        }
+        Value::Object(ref object) => {
+            let mut members: Vec<_> = object.iter().collect();
+            members.sort_unstable_by(|a, b| a.0.as_bytes().cmp(b.0.as_bytes()));
+
+            buf.push(b'{');
+            for (i, (key, item)) in members.into_iter().enumerate() {
+                if i != 0 {
+                    buf.push(b',');
+                }
+                serde_json::to_writer(&mut *buf, key)?;
+                buf.push(b':');
+                write_value(buf, item)?;
+            }
+            buf.push(b'}');
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns the canonical representation of a number.
+///
+/// Floating point numbers without a fractional part are converted to integers when they fit
+/// in an `i64` or a `u64`.
+fn normalize_number(n: &Number) -> Number {
+    match n.as_f64() {
+        Some(f) if n.is_f64() && f.fract() == 0.0 => {
+            if f >= i64::MIN as f64 && f < i64::MAX as f64 {
+                Number::from(f as i64)
+            } else if f >= 0.0 && f < u64::MAX as f64 {
+                Number::from(f as u64)
+            } else {
+                n.clone()
+            }
+        }
+        _ => n.clone(),
+    }
+}
+
+#[test]
+#[cfg(test)]
+fn canonical_form() {
+    use std::borrow::Cow;
+
+    let request = Request {
+        method: Cow::Borrowed("m"),
+        params: serde_json::json!({ "b": [1.0, -0.0, 1.5], "a": { "d": null, "c": "x" } }),
+        id: Some(crate::Id::Float(2.0)),
+    };
+
+    assert_eq!(
+        write_canonical_request(&request).unwrap(),
+        br#"{"id":2,"jsonrpc":"2.0","method":"m","params":{"a":{"c":"x","d":null},"b":[1,0,1.5]}}"#,
+    );
+}
+
+#[test]
+#[cfg(test)]
+fn semantic_equality() {
+    use std::borrow::Cow;
+
+    let a = Request {
+        method: Cow::Borrowed("m"),
+        params: serde_json::json!({ "a": 1.0, "b": [2] }),
+        id: Some(Id::Int(1)),
+    };
+    let b = Request {
+        method: Cow::Borrowed("m"),
+        params: serde_json::json!({ "b": [2.0], "a": 1 }),
+        id: Some(Id::Uint(1)),
+    };
+
+    assert!(a.semantically_eq(&b));
+    assert!(!a.semantically_eq(&Request {
+        id: None,
+        ..b.clone()
+    }));
+
+    let (a, b) = (a.normalize().unwrap(), b.normalize().unwrap());
+    assert_eq!(a.params, b.params);
+    assert_eq!(a.id, b.id);
+}
+
+#[test]
+#[cfg(all(test, feature = "hash"))]
+fn hash_ignores_key_order_and_id() {
+    use std::borrow::Cow;
+
+    let a = Request {
+        method: Cow::Borrowed("m"),
+        params: serde_json::json!({ "a": 1, "b": 2.0 }),
+        id: Some(crate::Id::Int(1)),
+    };
+    let b = Request {
+        method: Cow::Borrowed("m"),
+        params: serde_json::json!({ "b": 2, "a": 1 }),
+        id: None,
+    };
+    let c = Request {
+        method: Cow::Borrowed("n"),
+        params: serde_json::json!({ "a": 1, "b": 2 }),
+        id: None,
+    };
+
+    assert_eq!(canonical_hash(&a).unwrap(), canonical_hash(&b).unwrap());
+    assert_ne!(canonical_hash(&a).unwrap(), canonical_hash(&c).unwrap());
+}
+
+#[test]
+#[cfg(test)]
+fn wide_ids() {
+    use std::borrow::Cow;
+
+    let request = Request {
+        method: Cow::Borrowed("m"),
+        params: serde_json::json!({ "b": 1, "a": 2 }),
+        id: Some(Id::Uint128(u128::MAX)),
+    };
+    assert_eq!(
+        write_canonical_request(&request).unwrap(),
+        br#"{"id":340282366920938463463374607431768211455,"jsonrpc":"2.0","method":"m","params":{"a":2,"b":1}}"#,
+    );
+    assert!(request.semantically_eq(&request));
+    assert!(!request.semantically_eq(&Request {
+        id: Some(Id::Int128(i128::MIN)),
+        ..request.clone()
+    }));
+    assert!(request.semantically_eq(&Request {
+        id: Some(Id::Uint128(u128::MAX)),
+        params: serde_json::json!({ "a": 2, "b": 1.0 }),
+        ..request.clone()
+    }));
+
+    // Identifiers that fit in 64 bits are equal to their 128-bit counterparts.
+    let small = Request {
+        id: Some(Id::Int128(-1)),
+        ..request.clone()
+    };
+    assert!(small.semantically_eq(&Request {
+        id: Some(Id::Int(-1)),
+        ..request.clone()
+    }));
+    assert_eq!(small.normalize().unwrap().id, Some(Id::Int(-1)));
+
+    let response = Response::<(), ()> {
+        result: Err(crate::Error {
+            code: crate::ErrorCode::INTERNAL_ERROR,
+            message: Cow::Borrowed("oops"),
+            data: None,
+        }),
+        id: Id::Int128(i128::MIN),
+    };
+    assert_eq!(
+        write_canonical_response(&response).unwrap(),
+        br#"{"error":{"code":-32603,"message":"oops"},"id":-170141183460469231731687303715884105728,"jsonrpc":"2.0"}"#,
+    );
+
+    #[cfg(feature = "hash")]
+    assert_eq!(
+        canonical_hash(&request).unwrap(),
+        canonical_hash(&Request {
+            id: None,
+            ..request
+        })
+        .unwrap(),
+    );
+}
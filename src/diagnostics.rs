@@ -0,0 +1,147 @@
+//! Detailed reports of results that do not match their expected type.
+
+use std::fmt;
+
+use serde::Deserialize;
+use serde_json::value::RawValue;
+
+use crate::raw::EmptyAsUnit;
+use crate::Response;
+
+/// The error returned by [`read_response_diagnosed`].
+#[derive(Debug)]
+pub enum ResponseMismatch {
+    /// The response itself was not valid.
+    Envelope(serde_json::Error),
+    /// The response was valid, but its result did not match the expected type.
+    Result {
+        /// The name of the expected type.
+        expected: &'static str,
+        /// The raw JSON of the result that was received.
+        received: Box<str>,
+        /// The path of the first value of the result that did not match, such as
+        /// `items[0].name`, or `.` for the result itself.
+        path: String,
+        /// The error that occurred while deserializing that value.
+        error: serde_json::Error,
+    },
+}
+
+impl fmt::Display for ResponseMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Self::Envelope(ref error) => write!(f, "invalid response: {error}"),
+            Self::Result {
+                expected,
+                ref received,
+                ref path,
+                ref error,
+            } => write!(
+                f,
+                "result does not match `{expected}` at `{path}`: {error} (received {received})"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ResponseMismatch {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match *self {
+            Self::Envelope(ref error) | Self::Result { ref error, .. } => Some(error),
+        }
+    }
+}
+
+/// Reads a response from a slice of bytes, reporting precisely why its result does not match
+/// `T` when it doesn't.
+///
+/// This is slower than [`read_response`], as the result is first read as raw JSON, and is
+/// meant for debugging integrations with servers whose results are not well documented.
+///
+/// ```
+/// # use jsonrpc_sys::{read_response_diagnosed, ResponseMismatch};
+/// #[derive(Debug, serde::Deserialize)]
+/// struct Block {
+///     number: u64,
+/// }
+///
+/// let input = br#"{"jsonrpc":"2.0","result":{"number":"0x1"},"id":1}"#;
+/// match read_response_diagnosed::<Block, ()>(input).unwrap_err() {
+///     ResponseMismatch::Result { path, received, .. } => {
+///         assert_eq!(path, "number");
+///         assert_eq!(&*received, r#"{"number":"0x1"}"#);
+///     }
+///     ResponseMismatch::Envelope(_) => unreachable!(),
+/// }
+/// ```
+///
+/// [`read_response`]: crate::read_response
+pub fn read_response_diagnosed<'a, T, E>(
+    bytes: &'a [u8],
+) -> Result<Response<'a, T, E>, ResponseMismatch>
+where
+    T: Deserialize<'a>,
+    E: Deserialize<'a>,
+{
+    let response: Response<'a, Option<&'a RawValue>, E> =
+        serde_json::from_slice(bytes).map_err(ResponseMismatch::Envelope)?;
+
+    let result = match response.result {
+        Ok(raw) => {
+            let raw = raw.map_or("null", RawValue::get);
+            let mut deserializer = serde_json::Deserializer::from_str(raw);
+            // Like `read_response`, accept `{}` for unit results.
+            match serde_path_to_error::deserialize(EmptyAsUnit(&mut deserializer)) {
+                Ok(value) => Ok(value),
+                Err(error) => {
+                    return Err(ResponseMismatch::Result {
+                        expected: std::any::type_name::<T>(),
+                        received: raw.into(),
+                        path: error.path().to_string(),
+                        error: error.into_inner(),
+                    })
+                }
+            }
+        }
+        Err(error) => Err(error),
+    };
+
+    Ok(Response {
+        result,
+        id: response.id,
+    })
+}
+
+#[test]
+#[cfg(test)]
+fn diagnosed() {
+    #[derive(Debug, Deserialize)]
+    #[allow(dead_code)]
+    struct Item {
+        name: String,
+    }
+
+    let input = br#"{"jsonrpc":"2.0","result":{"items":[{"name":"a"},{"name":1}]},"id":1}"#;
+    let error = read_response_diagnosed::<std::collections::HashMap<String, Vec<Item>>, ()>(input)
+        .unwrap_err();
+    match error {
+        ResponseMismatch::Result { ref path, .. } => assert_eq!(path, "items[1].name"),
+        ResponseMismatch::Envelope(_) => panic!("{error}"),
+    }
+
+    let input = br#"{"jsonrpc":"2.0","result":null,"id":1}"#;
+    assert!(read_response_diagnosed::<Option<u32>, ()>(input).is_ok());
+    assert!(read_response_diagnosed::<(), ()>(input).is_ok());
+    let input = br#"{"jsonrpc":"2.0","result":{},"id":1}"#;
+    assert!(crate::read_response::<(), ()>(input).is_ok());
+    assert!(read_response_diagnosed::<(), ()>(input).is_ok());
+    let input = br#"{"jsonrpc":"2.0","error":{"code":1,"message":"m"},"id":1}"#;
+    assert!(read_response_diagnosed::<u32, ()>(input)
+        .unwrap()
+        .result
+        .is_err());
+    assert!(matches!(
+        read_response_diagnosed::<u32, ()>(b"{}"),
+        Err(ResponseMismatch::Envelope(_))
+    ));
+}
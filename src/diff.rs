@@ -0,0 +1,203 @@
+//! Structured comparison of JSON-RPC 2.0 responses.
+
+use std::borrow::Cow;
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::canonical::normalize_value;
+use crate::{Error, Id, Response};
+
+/// A difference between two responses, as found by [`diff_responses`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Difference {
+    /// The path of the values that differ, such as `$.result.items[0].name`.
+    ///
+    /// Keys that are not made of ASCII alphanumeric characters and underscores are written
+    /// as quoted JSON strings, such as `$.result["content-type"]`.
+    pub path: String,
+    /// The value found in the first response, or `None` if it is missing.
+    pub left: Option<Value>,
+    /// The value found in the second response, or `None` if it is missing.
+    pub right: Option<Value>,
+}
+
+/// Compares two responses, returning the list of their differences.
+///
+/// The identifiers of the responses are ignored, as are the values found at any of the
+/// `ignored` paths (and everything they contain), which is useful for volatile values such as
+/// timestamps. Like for [semantic equality], the order of the keys of objects and the way
+/// numbers are written are not considered differences.
+///
+/// Values are reported at the deepest path where they differ: two objects that only differ by
+/// one member produce a single [`Difference`] for that member.
+///
+/// ```
+/// # use jsonrpc_sys::{diff_responses, Id, Response};
+/// let a = Response::<_, ()> { result: Ok(serde_json::json!({"a": 1, "at": 10})), id: Id::Uint(1) };
+/// let b = Response::<_, ()> { result: Ok(serde_json::json!({"a": 2, "at": 20})), id: Id::Uint(2) };
+///
+/// let diff = diff_responses(&a, &b, &["$.result.at"]).unwrap();
+/// assert_eq!(diff.len(), 1);
+/// assert_eq!(diff[0].path, "$.result.a");
+/// ```
+///
+/// [semantic equality]: crate::Request::semantically_eq
+pub fn diff_responses<T, E, U, F>(
+    a: &Response<T, E>,
+    b: &Response<U, F>,
+    ignored: &[&str],
+) -> serde_json::Result<Vec<Difference>>
+where
+    T: Serialize,
+    E: Serialize,
+    U: Serialize,
+    F: Serialize,
+{
+    fn to_value<T: Serialize, E: Serialize>(
+        response: &Response<T, E>,
+    ) -> serde_json::Result<Value> {
+        // The identifier is replaced before serializing, as it is ignored anyway and may not
+        // fit in a `Value`.
+        let response = Response {
+            result: response.result.as_ref().map_err(|error| Error {
+                code: error.code,
+                message: Cow::Borrowed(&*error.message),
+                data: error.data.as_ref(),
+            }),
+            id: Id::Null,
+        };
+
+        let mut value = normalize_value(serde_json::to_value(&response)?);
+        if let Value::Object(ref mut members) = value {
+            members.remove("id");
+        }
+        Ok(value)
+    }
+
+    let mut diff = Vec::new();
+    let mut path = String::from("$");
+    diff_values(
+        &mut path,
+        Some(&to_value(a)?),
+        Some(&to_value(b)?),
+        ignored,
+        &mut diff,
+    );
+    Ok(diff)
+}
+
+/// Appends the differences between two values to `diff`.
+///
+/// `path` is restored to its original value before returning.
+fn diff_values(
+    path: &mut String,
+    left: Option<&Value>,
+    right: Option<&Value>,
+    ignored: &[&str],
+    diff: &mut Vec<Difference>,
+) {
+    if ignored.contains(&path.as_str()) {
+        return;
+    }
+
+    match (left, right) {
+        (Some(Value::Object(l)), Some(Value::Object(r))) => {
+            let mut keys: Vec<&String> = l.keys().chain(r.keys()).collect();
+            keys.sort_unstable();
+            keys.dedup();
+
+            for key in keys {
+                let len = path.len();
+                push_key(path, key);
+                diff_values(path, l.get(key), r.get(key), ignored, diff);
+                path.truncate(len);
+            }
+        }
+        (Some(Value::Array(l)), Some(Value::Array(r))) => {
+            for i in 0..l.len().max(r.len()) {
+                let len = path.len();
+                path.push_str(&format!("[{i}]"));
+                diff_values(path, l.get(i), r.get(i), ignored, diff);
+                path.truncate(len);
+            }
+        }
+        (l, r) if l == r => (),
+        (l, r) => diff.push(Difference {
+            path: path.clone(),
+            left: l.cloned(),
+            right: r.cloned(),
+        }),
+    }
+}
+
+/// Appends the path component of an object member to `path`.
+fn push_key(path: &mut String, key: &str) {
+    if !key.is_empty() && key.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'_') {
+        path.push('.');
+        path.push_str(key);
+    } else {
+        path.push('[');
+        path.push_str(&Value::from(key).to_string());
+        path.push(']');
+    }
+}
+
+#[test]
+#[cfg(test)]
+fn diff() {
+    use serde_json::json;
+
+    use crate::ErrorCode;
+
+    let a = Response::<_, ()> {
+        result: Ok(json!({"items": [1, 2.0], "a-b": true, "t": 1})),
+        id: Id::Uint(1),
+    };
+    let b = Response::<_, ()> {
+        result: Ok(json!({"t": 2, "items": [1.0, 3, 4], "a-b": true})),
+        id: Id::Str("x".into()),
+    };
+    assert!(diff_responses(&a, &a, &[]).unwrap().is_empty());
+    assert_eq!(
+        diff_responses(&a, &b, &["$.result.t"]).unwrap(),
+        [
+            Difference {
+                path: "$.result.items[1]".into(),
+                left: Some(json!(2)),
+                right: Some(json!(3)),
+            },
+            Difference {
+                path: "$.result.items[2]".into(),
+                left: None,
+                right: Some(json!(4)),
+            },
+        ]
+    );
+
+    let c = Response::<(), _> {
+        result: Err(Error {
+            code: ErrorCode::INTERNAL_ERROR,
+            message: "oops".into(),
+            data: Some(json!({"a-b": 1})),
+        }),
+        id: Id::Uint(1),
+    };
+    let diff = diff_responses(&a, &c, &[]).unwrap();
+    let paths: Vec<_> = diff.iter().map(|d| d.path.as_str()).collect();
+    assert_eq!(paths, ["$.error", "$.result"]);
+
+    let mut d = c.clone();
+    d.result.as_mut().unwrap_err().data = Some(json!({"a-b": 2}));
+    assert_eq!(diff_responses(&c, &d, &[]).unwrap().len(), 1);
+    assert!(diff_responses(&c, &d, &[r#"$.error.data["a-b"]"#])
+        .unwrap()
+        .is_empty());
+
+    let e = Response::<_, ()> {
+        id: Id::Uint128(u128::MAX),
+        ..a.clone()
+    };
+    assert!(diff_responses(&e, &e, &[]).unwrap().is_empty());
+    assert!(diff_responses(&a, &e, &[]).unwrap().is_empty());
+}
@@ -0,0 +1,112 @@
+use serde::{Deserialize, Serialize};
+
+/// One level of an [`ErrorChain`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ErrorChainEntry {
+    /// The message of the error at this level.
+    pub message: String,
+    /// The name of the type of the error at this level, if known.
+    ///
+    /// Sources are only available as `dyn Error` trait objects, so this is only known for the
+    /// top-level error.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub type_name: Option<String>,
+}
+
+/// The chain of sources of a [`std::error::Error`], suitable for the `data` member of a
+/// JSON-RPC 2.0 [`Error`].
+///
+/// This type serializes as an array of [`ErrorChainEntry`], starting with the top-level error.
+///
+/// # Examples
+///
+/// ```
+/// use jsonrpc_sys::ErrorChain;
+///
+/// let error = std::fs::read("/does/not/exist").unwrap_err();
+/// let mut chain = ErrorChain::new(&error);
+///
+/// // Avoid leaking details about the server in release builds.
+/// if !cfg!(debug_assertions) {
+///     chain.redact();
+/// }
+/// ```
+///
+/// [`Error`]: crate::Error
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ErrorChain(pub Vec<ErrorChainEntry>);
+
+impl ErrorChain {
+    /// Walks the chain of sources of the provided error.
+    pub fn new<E>(error: &E) -> Self
+    where
+        E: ?Sized + std::error::Error,
+    {
+        let mut entries = vec![ErrorChainEntry {
+            message: error.to_string(),
+            type_name: Some(std::any::type_name::<E>().to_owned()),
+        }];
+
+        let mut source = error.source();
+        while let Some(error) = source {
+            entries.push(ErrorChainEntry {
+                message: error.to_string(),
+                type_name: None,
+            });
+            source = error.source();
+        }
+
+        Self(entries)
+    }
+
+    /// Only keeps the first `max_depth` levels of the chain.
+    #[inline(always)]
+    pub fn truncate(&mut self, max_depth: usize) {
+        self.0.truncate(max_depth);
+    }
+
+    /// Removes the messages and type names of every level of the chain, only keeping its
+    /// length.
+    pub fn redact(&mut self) {
+        for entry in &mut self.0 {
+            entry.message.clear();
+            entry.type_name = None;
+        }
+    }
+}
+
+#[test]
+#[cfg(test)]
+fn walks_sources() {
+    #[derive(Debug)]
+    struct Outer(std::fmt::Error);
+
+    impl std::fmt::Display for Outer {
+        fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+            f.write_str("outer")
+        }
+    }
+
+    impl std::error::Error for Outer {
+        fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+            Some(&self.0)
+        }
+    }
+
+    let mut chain = ErrorChain::new(&Outer(std::fmt::Error));
+    assert_eq!(chain.0.len(), 2);
+    assert_eq!(chain.0[0].message, "outer");
+    assert!(chain.0[0].type_name.as_ref().unwrap().ends_with("Outer"));
+    assert_eq!(chain.0[1].type_name, None);
+
+    chain.truncate(1);
+    chain.redact();
+    assert_eq!(
+        chain.0,
+        [ErrorChainEntry {
+            message: String::new(),
+            type_name: None,
+        }]
+    );
+}
@@ -0,0 +1,170 @@
+//! Typed decoding of the `data` member of JSON-RPC 2.0 errors.
+
+use std::ops::RangeInclusive;
+
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use crate::{Error, ErrorCode, Response};
+
+/// The `data` member of an error decoded by an [`ErrorDataRegistry`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum ErrorData<D> {
+    /// The error code was registered and the data was successfully decoded.
+    Known(D),
+    /// The error code was not registered, or the data could not be decoded into the
+    /// registered type.
+    Raw(Value),
+}
+
+/// A function decoding the `data` member of an error.
+type Decoder<D> = Box<dyn Fn(Value) -> serde_json::Result<D> + Send + Sync>;
+
+/// A registry of decoders for the `data` member of JSON-RPC 2.0 errors, indexed by error code.
+///
+/// Servers often attach differently shaped `data` to different error codes. Instead of forcing
+/// a single type to cover every possible shape, clients can register one type per error code
+/// (or range of error codes), and convert each of them into a common type `D`, usually an
+/// enumeration of the known payloads.
+///
+/// # Examples
+///
+/// ```
+/// use jsonrpc_sys::{ErrorData, ErrorDataRegistry};
+///
+/// #[derive(Debug, PartialEq)]
+/// enum MyErrorData {
+///     Reverted(String),
+///     RetryAfter(u64),
+/// }
+///
+/// let mut registry = ErrorDataRegistry::new();
+/// registry
+///     .register(3, MyErrorData::Reverted)
+///     .register(-32005, MyErrorData::RetryAfter);
+///
+/// let response = registry
+///     .read_response::<()>(br#"{"jsonrpc":"2.0","error":{"code":3,"message":"","data":"0x"},"id":1}"#)
+///     .unwrap();
+/// let data = response.result.unwrap_err().data;
+/// assert_eq!(data, Some(ErrorData::Known(MyErrorData::Reverted("0x".into()))));
+/// ```
+pub struct ErrorDataRegistry<D> {
+    /// The registered decoders. When multiple ranges overlap, the last registered one wins.
+    decoders: Vec<(RangeInclusive<i64>, Decoder<D>)>,
+}
+
+impl<D> Default for ErrorDataRegistry<D> {
+    #[inline(always)]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<D> std::fmt::Debug for ErrorDataRegistry<D> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_list()
+            .entries(self.decoders.iter().map(|(codes, _)| codes))
+            .finish()
+    }
+}
+
+impl<D> ErrorDataRegistry<D> {
+    /// Creates a new empty [`ErrorDataRegistry`].
+    #[inline(always)]
+    pub const fn new() -> Self {
+        Self {
+            decoders: Vec::new(),
+        }
+    }
+
+    /// Registers the type of the `data` member of errors with the provided code.
+    ///
+    /// The data is deserialized as a `T`, then converted into a `D` using `map`.
+    pub fn register<T, F>(&mut self, code: impl Into<ErrorCode>, map: F) -> &mut Self
+    where
+        T: DeserializeOwned,
+        F: 'static + Send + Sync + Fn(T) -> D,
+    {
+        let code = code.into().0;
+        self.register_range(code..=code, map)
+    }
+
+    /// Registers the type of the `data` member of errors whose code is in the provided range.
+    ///
+    /// The data is deserialized as a `T`, then converted into a `D` using `map`.
+    pub fn register_range<T, F>(&mut self, codes: RangeInclusive<i64>, map: F) -> &mut Self
+    where
+        T: DeserializeOwned,
+        F: 'static + Send + Sync + Fn(T) -> D,
+    {
+        self.decoders.push((
+            codes,
+            Box::new(move |value| serde_json::from_value(value).map(&map)),
+        ));
+        self
+    }
+
+    /// Decodes the `data` member of an error with the provided code.
+    pub fn decode(&self, code: ErrorCode, data: Value) -> ErrorData<D> {
+        let decoder = self
+            .decoders
+            .iter()
+            .rev()
+            .find(|(codes, _)| codes.contains(&code.0));
+
+        match decoder {
+            Some((_, decoder)) => match decoder(data.clone()) {
+                Ok(data) => ErrorData::Known(data),
+                Err(_) => ErrorData::Raw(data),
+            },
+            None => ErrorData::Raw(data),
+        }
+    }
+
+    /// Decodes the `data` member of the provided error.
+    pub fn decode_error<'a>(&self, error: Error<'a, Value>) -> Error<'a, ErrorData<D>> {
+        Error {
+            code: error.code,
+            message: error.message,
+            data: error.data.map(|data| self.decode(error.code, data)),
+        }
+    }
+
+    /// Reads a response from a slice of bytes, decoding the `data` member of its error (if any)
+    /// using the registered decoders.
+    pub fn read_response<'a, T>(
+        &self,
+        bytes: &'a [u8],
+    ) -> serde_json::Result<Response<'a, T, ErrorData<D>>>
+    where
+        T: serde::Deserialize<'a>,
+    {
+        let response: Response<T, Value> = serde_json::from_slice(bytes)?;
+
+        Ok(Response {
+            result: response.result.map_err(|err| self.decode_error(err)),
+            id: response.id,
+        })
+    }
+}
+
+#[test]
+#[cfg(test)]
+fn fallback_to_raw() {
+    let mut registry = ErrorDataRegistry::new();
+    registry.register_range(-32099..=-32000, |n: u32| n);
+
+    assert_eq!(
+        registry.decode(ErrorCode(-32001), Value::from(4)),
+        ErrorData::Known(4)
+    );
+    assert_eq!(
+        registry.decode(ErrorCode(-32001), Value::from("x")),
+        ErrorData::Raw(Value::from("x"))
+    );
+    assert_eq!(
+        registry.decode(ErrorCode(1), Value::from(4)),
+        ErrorData::Raw(Value::from(4))
+    );
+}
@@ -0,0 +1,198 @@
+//! Serializes byte buffers as `0x`-prefixed hexadecimal data.
+//!
+//! This is the companion of the [`quantity`](crate::quantity) module for opaque data, such as
+//! hashes or addresses. Unlike quantities, data keeps its leading zeros, and always has an
+//! even number of digits: `"0x"` is the empty buffer and `"0x00ff"` is two bytes.
+//!
+//! Deserializing into a fixed-size array, such as `[u8; 32]`, checks that the data has exactly
+//! the right length. The functions of this module require the `0x` prefix; the [`lenient`]
+//! module does not.
+//!
+//! ```
+//! # use serde::{Deserialize, Serialize};
+//! #[derive(Serialize, Deserialize)]
+//! struct Transaction {
+//!     #[serde(with = "jsonrpc_sys::hex_data")]
+//!     hash: [u8; 32],
+//!     #[serde(with = "jsonrpc_sys::hex_data")]
+//!     input: Vec<u8>,
+//! }
+//! ```
+
+use std::fmt;
+use std::marker::PhantomData;
+
+use serde::de::{Error, Unexpected, Visitor};
+use serde::{Deserializer, Serializer};
+
+/// Serializes the provided bytes as hexadecimal data.
+pub fn serialize<T, S>(bytes: &T, serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: ?Sized + AsRef<[u8]>,
+    S: Serializer,
+{
+    serializer.serialize_str(&encode(bytes.as_ref(), "0x"))
+}
+
+/// Encodes bytes as lowercase hexadecimal digits, after the provided prefix.
+pub(crate) fn encode(bytes: &[u8], prefix: &str) -> String {
+    const DIGITS: &[u8; 16] = b"0123456789abcdef";
+
+    let mut s = String::with_capacity(prefix.len() + bytes.len() * 2);
+    s.push_str(prefix);
+    for &b in bytes {
+        s.push(DIGITS[usize::from(b >> 4)] as char);
+        s.push(DIGITS[usize::from(b & 0xf)] as char);
+    }
+    s
+}
+
+/// Deserializes bytes from hexadecimal data.
+pub fn deserialize<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: TryFrom<Vec<u8>>,
+{
+    deserializer.deserialize_str(DataVisitor::new(Prefix::Required, usize::MAX))
+}
+
+/// Lenient variants of the functions of the [`hex_data`](self) module.
+pub mod lenient {
+    use super::*;
+
+    /// Serializes the provided bytes as hexadecimal data.
+    ///
+    /// This is the same as [`hex_data::serialize`](super::serialize).
+    pub fn serialize<T, S>(bytes: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: ?Sized + AsRef<[u8]>,
+        S: Serializer,
+    {
+        super::serialize(bytes, serializer)
+    }
+
+    /// Deserializes bytes from hexadecimal data.
+    ///
+    /// The `0x` prefix is optional, and may be uppercase.
+    pub fn deserialize<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+    where
+        D: Deserializer<'de>,
+        T: TryFrom<Vec<u8>>,
+    {
+        deserializer.deserialize_str(DataVisitor::new(Prefix::Optional, usize::MAX))
+    }
+}
+
+/// Whether hexadecimal data starts with a `0x` prefix.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Prefix {
+    /// The data must start with `0x`.
+    Required,
+    /// The data may start with `0x` or `0X`.
+    Optional,
+    /// The data is made of digits only.
+    Forbidden,
+}
+
+/// Parses hexadecimal data of at most `max_len` bytes.
+pub(crate) struct DataVisitor<T> {
+    prefix: Prefix,
+    max_len: usize,
+    marker: PhantomData<T>,
+}
+
+impl<T> DataVisitor<T> {
+    pub(crate) fn new(prefix: Prefix, max_len: usize) -> Self {
+        Self {
+            prefix,
+            max_len,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<'de, T> Visitor<'de> for DataVisitor<T>
+where
+    T: TryFrom<Vec<u8>>,
+{
+    type Value = T;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("hexadecimal data")
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: Error,
+    {
+        fn digit(b: u8) -> Option<u8> {
+            (b as char).to_digit(16).map(|d| d as u8)
+        }
+
+        let invalid = || E::invalid_value(Unexpected::Str(v), &self);
+
+        let digits = match self.prefix {
+            Prefix::Required => v.strip_prefix("0x").ok_or_else(invalid)?,
+            Prefix::Optional => v
+                .strip_prefix("0x")
+                .or_else(|| v.strip_prefix("0X"))
+                .unwrap_or(v),
+            Prefix::Forbidden => v,
+        };
+
+        if digits.len() % 2 != 0 {
+            return Err(invalid());
+        }
+
+        if digits.len() / 2 > self.max_len {
+            return Err(E::invalid_length(digits.len() / 2, &self));
+        }
+
+        let bytes = digits
+            .as_bytes()
+            .chunks_exact(2)
+            .map(|pair| Some(digit(pair[0])? << 4 | digit(pair[1])?))
+            .collect::<Option<Vec<u8>>>()
+            .ok_or_else(invalid)?;
+
+        let len = bytes.len();
+        T::try_from(bytes).map_err(|_| E::invalid_length(len, &self))
+    }
+}
+
+#[test]
+#[cfg(test)]
+fn hex_data() {
+    #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+    struct Strict(#[serde(with = "self")] Vec<u8>);
+
+    #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+    struct Fixed(#[serde(with = "lenient")] [u8; 2]);
+
+    assert_eq!(serde_json::to_string(&Strict(vec![])).unwrap(), r#""0x""#);
+    assert_eq!(
+        serde_json::to_string(&Strict(vec![0, 0xff])).unwrap(),
+        r#""0x00ff""#
+    );
+    assert_eq!(
+        serde_json::from_str::<Strict>(r#""0x00FF""#).unwrap(),
+        Strict(vec![0, 0xff])
+    );
+    for invalid in [r#""00ff""#, r#""0x0""#, r#""0xgg""#, r#""0X00""#] {
+        assert!(
+            serde_json::from_str::<Strict>(invalid).is_err(),
+            "{invalid}"
+        );
+    }
+
+    assert_eq!(
+        serde_json::from_str::<Fixed>(r#""00ff""#).unwrap(),
+        Fixed([0, 0xff])
+    );
+    assert_eq!(
+        serde_json::from_str::<Fixed>(r#""0X00ff""#).unwrap(),
+        Fixed([0, 0xff])
+    );
+    assert!(serde_json::from_str::<Fixed>(r#""0x00""#).is_err());
+    assert!(serde_json::from_str::<Fixed>(r#""0x000000""#).is_err());
+}
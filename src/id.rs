@@ -8,7 +8,11 @@ use serde::ser::{Serialize, Serializer};
 /// JSON-RPC 2.0 clients can use this to match responses sent back by a complying server
 /// with the request they sent. This is especially useful when sending multiple requests
 /// at the same time without waiting for a response in between.
+///
+/// This enum is non-exhaustive, as the set of variants depends on the features of this crate
+/// that are enabled anywhere in the dependency graph.
 #[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
 pub enum Id<'a> {
     /// The ID was `null`.
     Null,
@@ -18,6 +22,18 @@ pub enum Id<'a> {
     Int(i64),
     /// The ID was an unsigned integer.
     Uint(u64),
+    /// The ID was a signed integer that does not fit in an `i64`.
+    ///
+    /// # Note
+    ///
+    /// `serde_json` only produces this variant when the `arbitrary_precision` feature is
+    /// enabled. Otherwise, integers that do not fit in an `i64` or a `u64` are parsed as
+    /// floating point numbers.
+    Int128(i128),
+    /// The ID was an unsigned integer that does not fit in a `u64`.
+    ///
+    /// See [`Id::Int128`] for more information.
+    Uint128(u128),
     /// The ID was a floating point number.
     ///
     /// # Note
@@ -26,6 +42,18 @@ pub enum Id<'a> {
     /// values as request IDs, but they technically are legal. For this reason, we have to
     /// account for them.
     Float(f64),
+    /// The ID was a number that could not be represented losslessly by any of the other
+    /// variants.
+    ///
+    /// # Note
+    ///
+    /// This variant is only available with the `arbitrary_precision` feature, which enables
+    /// the feature of the same name in `serde_json`. When it is enabled, numbers that do not
+    /// fit in an `i64`, a `u64`, an `i128` or a `u128`, and that would not be written back the
+    /// same way as an [`Id::Float`] (such as `1e3` or `0.10000000000000000001`), are
+    /// deserialized as this variant, preserving their exact representation.
+    #[cfg(feature = "arbitrary_precision")]
+    Number(serde_json::Number),
 }
 
 impl<'a> From<i64> for Id<'a> {
@@ -42,6 +70,20 @@ impl<'a> From<u64> for Id<'a> {
     }
 }
 
+impl<'a> From<i128> for Id<'a> {
+    #[inline(always)]
+    fn from(id: i128) -> Self {
+        Self::Int128(id)
+    }
+}
+
+impl<'a> From<u128> for Id<'a> {
+    #[inline(always)]
+    fn from(id: u128) -> Self {
+        Self::Uint128(id)
+    }
+}
+
 impl<'a> From<f64> for Id<'a> {
     #[inline(always)]
     fn from(id: f64) -> Self {
@@ -71,7 +113,31 @@ impl<'a> From<Cow<'a, str>> for Id<'a> {
 }
 
 impl<'a> Id<'a> {
-    /// Reborrows this [`Id`], creating a new instance without reallocating.
+    /// Returns an estimate of the number of bytes taken by this [`Id`] once serialized to JSON.
+    ///
+    /// The estimate is exact for `null` and integers, and ignores escape sequences in strings.
+    pub fn size_hint(&self) -> usize {
+        fn digits(n: u128) -> usize {
+            n.checked_ilog10().map_or(1, |d| d as usize + 1)
+        }
+
+        match *self {
+            Self::Null => 4,
+            Self::Str(ref s) => s.len() + 2,
+            Self::Int(i) => digits(i.unsigned_abs().into()) + usize::from(i < 0),
+            Self::Uint(u) => digits(u.into()),
+            Self::Int128(i) => digits(i.unsigned_abs()) + usize::from(i < 0),
+            Self::Uint128(u) => digits(u),
+            Self::Float(_) => 24,
+            #[cfg(feature = "arbitrary_precision")]
+            Self::Number(_) => 24,
+        }
+    }
+
+    /// Reborrows this [`Id`], creating a new instance that borrows its string, if any.
+    ///
+    /// This does not allocate, except for the `Number` variant of the `arbitrary_precision`
+    /// feature, whose number is cloned.
     pub fn reborrow<'b>(&'b self) -> Id<'b>
     where
         'b: 'a,
@@ -81,7 +147,26 @@ impl<'a> Id<'a> {
             Self::Str(ref s) => Self::Str(Cow::Borrowed(s)),
             Self::Int(i) => Self::Int(i),
             Self::Uint(u) => Self::Uint(u),
+            Self::Int128(i) => Self::Int128(i),
+            Self::Uint128(u) => Self::Uint128(u),
             Self::Float(f) => Self::Float(f),
+            #[cfg(feature = "arbitrary_precision")]
+            Self::Number(ref n) => Self::Number(n.clone()),
+        }
+    }
+
+    /// Converts this [`Id`] into one that owns its string, if any.
+    pub fn into_owned(self) -> Id<'static> {
+        match self {
+            Self::Null => Id::Null,
+            Self::Str(s) => Id::Str(Cow::Owned(s.into_owned())),
+            Self::Int(i) => Id::Int(i),
+            Self::Uint(u) => Id::Uint(u),
+            Self::Int128(i) => Id::Int128(i),
+            Self::Uint128(u) => Id::Uint128(u),
+            Self::Float(f) => Id::Float(f),
+            #[cfg(feature = "arbitrary_precision")]
+            Self::Number(n) => Id::Number(n),
         }
     }
 }
@@ -97,6 +182,10 @@ impl<'a> Serialize for Id<'a> {
             Self::Str(ref s) => serializer.serialize_str(s),
             Self::Int(i) => serializer.serialize_i64(i),
             Self::Uint(u) => serializer.serialize_u64(u),
+            Self::Int128(i) => serializer.serialize_i128(i),
+            Self::Uint128(u) => serializer.serialize_u128(u),
+            #[cfg(feature = "arbitrary_precision")]
+            Self::Number(ref n) => n.serialize(serializer),
         }
     }
 }
@@ -167,14 +256,114 @@ where
                 Ok(crate::Id::Uint(v))
             }
 
+            fn visit_i128<E>(self, v: i128) -> Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                Ok(crate::Id::Int128(v))
+            }
+
+            fn visit_u128<E>(self, v: u128) -> Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                Ok(crate::Id::Uint128(v))
+            }
+
             fn visit_f64<E>(self, v: f64) -> Result<Self::Value, E>
             where
                 E: serde::de::Error,
             {
                 Ok(crate::Id::Float(v))
             }
+
+            #[cfg(feature = "arbitrary_precision")]
+            fn visit_map<A>(self, map: A) -> Result<Self::Value, A::Error>
+            where
+                A: serde::de::MapAccess<'de>,
+            {
+                // With `arbitrary_precision`, `serde_json` hands numbers that don't fit in
+                // an `i64` or a `u64` as a map with a single private key.
+                let n = serde_json::Number::deserialize(
+                    serde::de::value::MapAccessDeserializer::new(map),
+                )?;
+
+                if let Some(u) = n.as_u128() {
+                    Ok(crate::Id::Uint128(u))
+                } else if let Some(i) = n.as_i128() {
+                    Ok(crate::Id::Int128(i))
+                } else if let Some(f) = n
+                    .as_f64()
+                    .filter(|&f| serde_json::Number::from_f64(f).is_some_and(|m| m == n))
+                {
+                    Ok(crate::Id::Float(f))
+                } else {
+                    Ok(crate::Id::Number(n))
+                }
+            }
         }
 
         deserializer.deserialize_option(IdVisitor)
     }
 }
+
+#[test]
+#[cfg(test)]
+fn size_hint() {
+    for id in [
+        Id::Null,
+        Id::from("abc"),
+        Id::Int(0),
+        Id::Int(-10),
+        Id::Uint(u64::MAX),
+        Id::Int128(i128::MIN),
+    ] {
+        assert_eq!(id.size_hint(), serde_json::to_string(&id).unwrap().len());
+    }
+}
+
+#[test]
+#[cfg(test)]
+fn int128() {
+    use serde::de::IntoDeserializer;
+
+    let de = IntoDeserializer::<serde::de::value::Error>::into_deserializer(u128::MAX);
+    assert_eq!(Id::deserialize(de), Ok(Id::Uint128(u128::MAX)));
+    assert_eq!(
+        serde_json::to_string(&Id::Int128(i128::MIN)).unwrap(),
+        i128::MIN.to_string()
+    );
+}
+
+#[test]
+#[cfg(all(test, feature = "arbitrary_precision"))]
+fn arbitrary_precision() {
+    let json = "340282366920938463463374607431768211456";
+    let id: Id = serde_json::from_str(json).unwrap();
+    assert!(matches!(id, Id::Number(_)));
+    assert_eq!(serde_json::to_string(&id).unwrap(), json);
+
+    let json = "340282366920938463463374607431768211455";
+    let id: Id = serde_json::from_str(json).unwrap();
+    assert_eq!(id, Id::Uint128(u128::MAX));
+    assert_eq!(serde_json::to_string(&id).unwrap(), json);
+
+    let json = "-170141183460469231731687303715884105728";
+    let id: Id = serde_json::from_str(json).unwrap();
+    assert_eq!(id, Id::Int128(i128::MIN));
+
+    let id: Id = serde_json::from_str("1.5").unwrap();
+    assert_eq!(id, Id::Float(1.5));
+    assert_eq!(serde_json::to_string(&id).unwrap(), "1.5");
+
+    let id: Id = serde_json::from_str("1.0").unwrap();
+    assert_eq!(id, Id::Float(1.0));
+
+    let id: Id = serde_json::from_str("1e3").unwrap();
+    assert!(matches!(id, Id::Number(_)));
+
+    let json = "0.10000000000000000001";
+    let id: Id = serde_json::from_str(json).unwrap();
+    assert!(matches!(id, Id::Number(_)));
+    assert_eq!(serde_json::to_string(&id).unwrap(), json);
+}
@@ -0,0 +1,122 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+use crate::Request;
+
+/// A registry mapping a known set of method names to `&'static str`s and small integer IDs.
+///
+/// Servers usually know every method they support in advance. Interning incoming method names
+/// against that set avoids allocating owned copies of them, and lets dispatch and metrics use
+/// the integer ID instead of hashing or comparing strings over and over again.
+///
+/// # Examples
+///
+/// ```
+/// use jsonrpc_sys::Interner;
+///
+/// let mut interner = Interner::new();
+/// let add = interner.register("add");
+/// let sub = interner.register("sub");
+///
+/// match interner.get("sub") {
+///     Some(id) if id == add => unreachable!(),
+///     Some(id) if id == sub => (),
+///     _ => unreachable!(),
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Interner {
+    /// Maps method names to their IDs.
+    ids: HashMap<&'static str, usize>,
+    /// Maps IDs to their method names.
+    names: Vec<&'static str>,
+}
+
+impl Interner {
+    /// Creates a new empty [`Interner`].
+    #[inline(always)]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a method name, returning its ID.
+    ///
+    /// IDs are attributed sequentially, starting at `0`. Registering the same name twice
+    /// returns the ID it was first given.
+    pub fn register(&mut self, name: &'static str) -> usize {
+        *self.ids.entry(name).or_insert_with(|| {
+            self.names.push(name);
+            self.names.len() - 1
+        })
+    }
+
+    /// Returns the ID of the provided method name, if it was registered.
+    #[inline]
+    pub fn get(&self, name: &str) -> Option<usize> {
+        self.ids.get(name).copied()
+    }
+
+    /// Returns the interned version of the provided method name, if it was registered.
+    #[inline]
+    pub fn resolve(&self, name: &str) -> Option<&'static str> {
+        self.ids.get_key_value(name).map(|(name, _)| *name)
+    }
+
+    /// Returns the method name associated with the provided ID.
+    #[inline]
+    pub fn name(&self, id: usize) -> Option<&'static str> {
+        self.names.get(id).copied()
+    }
+
+    /// Returns the number of registered method names.
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    /// Returns whether no method name was registered.
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    /// Replaces the method name of the provided request with its interned version, returning
+    /// its ID.
+    ///
+    /// If the method name was not registered, the request is left untouched and `None` is
+    /// returned.
+    pub fn intern<P>(&self, request: &mut Request<'_, P>) -> Option<usize> {
+        let (&name, &id) = self.ids.get_key_value(&*request.method)?;
+        request.method = Cow::Borrowed(name);
+        Some(id)
+    }
+}
+
+impl FromIterator<&'static str> for Interner {
+    fn from_iter<I: IntoIterator<Item = &'static str>>(iter: I) -> Self {
+        let mut interner = Self::new();
+        iter.into_iter().for_each(|name| {
+            interner.register(name);
+        });
+        interner
+    }
+}
+
+#[test]
+#[cfg(test)]
+fn intern() {
+    let interner: Interner = ["a", "b", "a"].into_iter().collect();
+    assert_eq!(interner.len(), 2);
+    assert_eq!(interner.name(1), Some("b"));
+
+    let mut request = Request {
+        method: Cow::Owned(String::from("b")),
+        params: (),
+        id: None,
+    };
+    assert_eq!(interner.intern(&mut request), Some(1));
+    assert!(matches!(request.method, Cow::Borrowed("b")));
+
+    request.method = Cow::Borrowed("c");
+    assert_eq!(interner.intern(&mut request), None);
+}
@@ -13,9 +13,23 @@ pub use self::request::*;
 mod response;
 pub use self::response::*;
 
+mod raw;
+
 mod batch;
 pub use self::batch::*;
 
+mod error_chain;
+pub use self::error_chain::*;
+
+mod interner;
+pub use self::interner::*;
+
+mod method_name;
+pub use self::method_name::*;
+
+mod redact;
+pub use self::redact::*;
+
 #[cfg(feature = "unknown_params")]
 mod unknown_params;
 #[cfg(feature = "unknown_params")]
@@ -25,3 +39,55 @@ pub use self::unknown_params::*;
 mod utils;
 #[cfg(feature = "utils")]
 pub use self::utils::*;
+
+#[cfg(feature = "utils")]
+mod canonical;
+#[cfg(feature = "utils")]
+pub use self::canonical::*;
+
+#[cfg(feature = "utils")]
+mod diff;
+#[cfg(feature = "utils")]
+pub use self::diff::*;
+
+#[cfg(feature = "utils")]
+mod error_data;
+#[cfg(feature = "utils")]
+pub use self::error_data::*;
+
+#[cfg(feature = "utils")]
+mod owned;
+#[cfg(feature = "utils")]
+pub use self::owned::*;
+
+#[cfg(feature = "utils")]
+mod stream;
+#[cfg(feature = "utils")]
+pub use self::stream::*;
+
+#[cfg(feature = "utils")]
+mod non_finite;
+#[cfg(feature = "utils")]
+pub use self::non_finite::*;
+
+#[cfg(feature = "diagnostics")]
+mod diagnostics;
+#[cfg(feature = "diagnostics")]
+pub use self::diagnostics::*;
+
+#[cfg(feature = "bson")]
+mod bson;
+#[cfg(feature = "bson")]
+pub use self::bson::*;
+
+#[cfg(feature = "base64")]
+pub mod bytes_base64;
+
+pub mod bytes_hex;
+pub mod hex_data;
+pub mod quantity;
+
+pub mod prelude;
+
+#[cfg(feature = "testing")]
+pub mod testing;
@@ -0,0 +1,153 @@
+use std::borrow::Cow;
+use std::fmt;
+
+use crate::{Error, ErrorCode, Request};
+
+/// A set of rules that method names must follow.
+///
+/// The specification places no restriction on method names besides reserving the ones starting
+/// with `rpc.`. Servers can use this type to reject garbage method names early, before they
+/// reach dispatch or logs.
+///
+/// # Examples
+///
+/// ```
+/// use jsonrpc_sys::MethodNameRules;
+///
+/// let rules = MethodNameRules {
+///     max_len: 32,
+///     allowed: |c| c.is_ascii_alphanumeric() || c == '_' || c == '.',
+///     ..Default::default()
+/// };
+///
+/// assert!(rules.validate("eth_call").is_ok());
+/// assert!(rules.validate("eth call").is_err());
+/// assert!(rules.validate("").is_err());
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct MethodNameRules {
+    /// The maximum length of a method name, in bytes.
+    ///
+    /// Defaults to 256.
+    pub max_len: usize,
+    /// Whether the empty method name is allowed.
+    ///
+    /// Defaults to `false`.
+    pub allow_empty: bool,
+    /// Whether method names starting with `rpc.` are allowed.
+    ///
+    /// The specification reserves those names for extensions. Defaults to `true`.
+    pub allow_reserved: bool,
+    /// Returns whether a character is allowed in method names.
+    ///
+    /// Defaults to allowing everything except control characters.
+    pub allowed: fn(char) -> bool,
+}
+
+impl Default for MethodNameRules {
+    fn default() -> Self {
+        Self {
+            max_len: 256,
+            allow_empty: false,
+            allow_reserved: true,
+            allowed: |c| !c.is_control(),
+        }
+    }
+}
+
+impl MethodNameRules {
+    /// Checks whether the provided method name follows these rules.
+    pub fn validate(&self, method: &str) -> Result<(), InvalidMethodName> {
+        if method.is_empty() && !self.allow_empty {
+            return Err(InvalidMethodName::Empty);
+        }
+        if method.len() > self.max_len {
+            return Err(InvalidMethodName::TooLong {
+                len: method.len(),
+                max_len: self.max_len,
+            });
+        }
+        if !self.allow_reserved && method.starts_with("rpc.") {
+            return Err(InvalidMethodName::Reserved);
+        }
+        if let Some(c) = method.chars().find(|&c| !(self.allowed)(c)) {
+            return Err(InvalidMethodName::DisallowedChar(c));
+        }
+        Ok(())
+    }
+
+    /// Checks whether the method name of the provided request follows these rules.
+    ///
+    /// On failure, the returned error is an [`ErrorCode::INVALID_REQUEST`] error that can be
+    /// sent back to the client.
+    pub fn validate_request<P, E>(&self, request: &Request<P>) -> Result<(), Error<'static, E>> {
+        self.validate(&request.method).map_err(Error::from)
+    }
+}
+
+/// The error returned by [`MethodNameRules::validate`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InvalidMethodName {
+    /// The method name was empty.
+    Empty,
+    /// The method name was too long.
+    TooLong {
+        /// The length of the method name, in bytes.
+        len: usize,
+        /// The maximum length allowed.
+        max_len: usize,
+    },
+    /// The method name was reserved by the specification.
+    Reserved,
+    /// The method name contained a disallowed character.
+    DisallowedChar(char),
+}
+
+impl fmt::Display for InvalidMethodName {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Self::Empty => f.write_str("method name is empty"),
+            Self::TooLong { len, max_len } => write!(
+                f,
+                "method name is {len} bytes long, but the maximum is {max_len}"
+            ),
+            Self::Reserved => f.write_str("method names starting with `rpc.` are reserved"),
+            Self::DisallowedChar(c) => write!(f, "method name contains disallowed character {c:?}"),
+        }
+    }
+}
+
+impl std::error::Error for InvalidMethodName {}
+
+impl<E> From<InvalidMethodName> for Error<'static, E> {
+    fn from(error: InvalidMethodName) -> Self {
+        Self {
+            code: ErrorCode::INVALID_REQUEST,
+            message: Cow::Owned(error.to_string()),
+            data: None,
+        }
+    }
+}
+
+#[test]
+#[cfg(test)]
+fn default_rules() {
+    let rules = MethodNameRules::default();
+    assert_eq!(
+        rules.validate("a\u{0}"),
+        Err(InvalidMethodName::DisallowedChar('\0'))
+    );
+    assert_eq!(rules.validate("rpc.discover"), Ok(()));
+    assert!(matches!(
+        rules.validate(&"a".repeat(257)),
+        Err(InvalidMethodName::TooLong { len: 257, .. })
+    ));
+
+    let request = Request {
+        method: Cow::Borrowed(""),
+        params: (),
+        id: None,
+    };
+    let error = rules.validate_request::<_, ()>(&request).unwrap_err();
+    assert_eq!(error.code, ErrorCode::INVALID_REQUEST);
+}
@@ -0,0 +1,262 @@
+//! Handling of non-finite floating point numbers.
+//!
+//! JSON has no representation for `NaN` or infinities. Some peers still emit them as bare
+//! `NaN`, `Infinity` or `-Infinity` tokens, which are rejected by strict parsers, while
+//! `serde_json` silently serializes them as `null`.
+
+use std::borrow::Cow;
+
+use serde::ser::{self, Serialize, Serializer};
+
+/// Replaces the bare `NaN`, `Infinity` and `-Infinity` tokens of a JSON document with `null`.
+///
+/// Strings are left untouched. When the document contains no such token, it is returned as-is
+/// without allocating, and can be passed to [`read_request`] or [`read_response`].
+///
+/// [`read_request`]: crate::read_request
+/// [`read_response`]: crate::read_response
+pub fn replace_non_finite(bytes: &[u8]) -> Cow<'_, [u8]> {
+    const TOKENS: [&[u8]; 3] = [b"-Infinity", b"Infinity", b"NaN"];
+
+    let mut output: Option<Vec<u8>> = None;
+    let mut in_string = false;
+    let mut escaped = false;
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let b = bytes[i];
+
+        if in_string {
+            if escaped {
+                escaped = false;
+            } else if b == b'\\' {
+                escaped = true;
+            } else if b == b'"' {
+                in_string = false;
+            }
+        } else if b == b'"' {
+            in_string = true;
+        } else if let Some(token) = TOKENS.iter().find(|t| bytes[i..].starts_with(t)) {
+            let output = output.get_or_insert_with(|| bytes[..i].to_vec());
+            output.extend_from_slice(b"null");
+            i += token.len();
+            continue;
+        }
+
+        if let Some(output) = &mut output {
+            output.push(b);
+        }
+        i += 1;
+    }
+
+    match output {
+        Some(output) => Cow::Owned(output),
+        None => Cow::Borrowed(bytes),
+    }
+}
+
+/// Checks that the provided value contains no non-finite floating point number.
+///
+/// `serde_json` serializes `NaN` and infinities as `null`, which silently changes the meaning
+/// of a message. Calling this function before one of the writers of this crate turns this into
+/// a clear error instead.
+pub fn check_finite<T>(value: &T) -> serde_json::Result<()>
+where
+    T: ?Sized + Serialize,
+{
+    value.serialize(FiniteChecker)
+}
+
+/// A [`Serializer`] that discards its input, but fails on non-finite floating point numbers.
+struct FiniteChecker;
+
+impl FiniteChecker {
+    fn check(v: f64) -> serde_json::Result<()> {
+        if v.is_finite() {
+            Ok(())
+        } else {
+            Err(ser::Error::custom(format_args!(
+                "non-finite floating point number `{v}` cannot be represented in JSON"
+            )))
+        }
+    }
+}
+
+macro_rules! ignore {
+    ($($name:ident($($ty:ty),*);)*) => {
+        $(
+            #[inline(always)]
+            fn $name(self, $(_: $ty),*) -> serde_json::Result<()> {
+                Ok(())
+            }
+        )*
+    };
+}
+
+impl Serializer for FiniteChecker {
+    type Ok = ();
+    type Error = serde_json::Error;
+    type SerializeSeq = Self;
+    type SerializeTuple = Self;
+    type SerializeTupleStruct = Self;
+    type SerializeTupleVariant = Self;
+    type SerializeMap = Self;
+    type SerializeStruct = Self;
+    type SerializeStructVariant = Self;
+
+    ignore! {
+        serialize_bool(bool);
+        serialize_i8(i8);
+        serialize_i16(i16);
+        serialize_i32(i32);
+        serialize_i64(i64);
+        serialize_i128(i128);
+        serialize_u8(u8);
+        serialize_u16(u16);
+        serialize_u32(u32);
+        serialize_u64(u64);
+        serialize_u128(u128);
+        serialize_char(char);
+        serialize_str(&str);
+        serialize_bytes(&[u8]);
+        serialize_none();
+        serialize_unit();
+        serialize_unit_struct(&'static str);
+        serialize_unit_variant(&'static str, u32, &'static str);
+    }
+
+    fn serialize_f32(self, v: f32) -> serde_json::Result<()> {
+        Self::check(v.into())
+    }
+
+    fn serialize_f64(self, v: f64) -> serde_json::Result<()> {
+        Self::check(v)
+    }
+
+    fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> serde_json::Result<()> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(
+        self,
+        _: &'static str,
+        value: &T,
+    ) -> serde_json::Result<()> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        value: &T,
+    ) -> serde_json::Result<()> {
+        value.serialize(self)
+    }
+
+    fn serialize_seq(self, _: Option<usize>) -> serde_json::Result<Self> {
+        Ok(self)
+    }
+
+    fn serialize_tuple(self, _: usize) -> serde_json::Result<Self> {
+        Ok(self)
+    }
+
+    fn serialize_tuple_struct(self, _: &'static str, _: usize) -> serde_json::Result<Self> {
+        Ok(self)
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: usize,
+    ) -> serde_json::Result<Self> {
+        Ok(self)
+    }
+
+    fn serialize_map(self, _: Option<usize>) -> serde_json::Result<Self> {
+        Ok(self)
+    }
+
+    fn serialize_struct(self, _: &'static str, _: usize) -> serde_json::Result<Self> {
+        Ok(self)
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: usize,
+    ) -> serde_json::Result<Self> {
+        Ok(self)
+    }
+}
+
+macro_rules! compound {
+    ($($trait:ident::$method:ident($($key:ty),*);)*) => {
+        $(
+            impl ser::$trait for FiniteChecker {
+                type Ok = ();
+                type Error = serde_json::Error;
+
+                fn $method<T: ?Sized + Serialize>(
+                    &mut self,
+                    $(_: $key,)*
+                    value: &T,
+                ) -> serde_json::Result<()> {
+                    value.serialize(FiniteChecker)
+                }
+
+                #[inline(always)]
+                fn end(self) -> serde_json::Result<()> {
+                    Ok(())
+                }
+            }
+        )*
+    };
+}
+
+compound! {
+    SerializeSeq::serialize_element();
+    SerializeTuple::serialize_element();
+    SerializeTupleStruct::serialize_field();
+    SerializeTupleVariant::serialize_field();
+    SerializeStruct::serialize_field(&'static str);
+    SerializeStructVariant::serialize_field(&'static str);
+}
+
+impl ser::SerializeMap for FiniteChecker {
+    type Ok = ();
+    type Error = serde_json::Error;
+
+    fn serialize_key<T: ?Sized + Serialize>(&mut self, key: &T) -> serde_json::Result<()> {
+        key.serialize(FiniteChecker)
+    }
+
+    fn serialize_value<T: ?Sized + Serialize>(&mut self, value: &T) -> serde_json::Result<()> {
+        value.serialize(FiniteChecker)
+    }
+
+    #[inline(always)]
+    fn end(self) -> serde_json::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+#[cfg(test)]
+fn non_finite() {
+    let input = br#"{"a":NaN,"b":[-Infinity,Infinity],"c":"NaN \"Infinity"}"#;
+    assert_eq!(
+        &*replace_non_finite(input),
+        br#"{"a":null,"b":[null,null],"c":"NaN \"Infinity"}"#
+    );
+    assert!(matches!(replace_non_finite(b"[1.5]"), Cow::Borrowed(_)));
+
+    assert!(check_finite(&crate::Id::Float(1.5)).is_ok());
+    assert!(check_finite(&(1, [f64::NAN])).is_err());
+}
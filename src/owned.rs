@@ -0,0 +1,128 @@
+use serde::Deserialize;
+
+use crate::{MaybeBatchedRequests, Request, Response};
+
+/// The raw bytes of a JSON-RPC 2.0 message.
+///
+/// The envelope types of this crate borrow from the buffer they were parsed from, so they
+/// cannot outlive it. This type simply owns that buffer, so that a message can be moved across
+/// threads or into work queues, and viewed as a [`Request`] or a [`Response`] where it is
+/// handled.
+///
+/// Views are not cached: every call to [`OwnedMessage::request`], [`OwnedMessage::requests`]
+/// or [`OwnedMessage::response`] parses the message again.
+///
+/// ```
+/// # use jsonrpc_sys::OwnedMessage;
+/// let message = OwnedMessage::from(r#"{"jsonrpc":"2.0","method":"ping"}"#.to_owned());
+/// let message = std::thread::spawn(move || message).join().unwrap();
+///
+/// let request = message.request::<Option<()>>().unwrap();
+/// assert_eq!(request.method, "ping");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct OwnedMessage {
+    bytes: Box<[u8]>,
+}
+
+impl OwnedMessage {
+    /// Creates a new [`OwnedMessage`] from the raw bytes of a message.
+    ///
+    /// The bytes are not validated.
+    #[inline]
+    pub fn new(bytes: impl Into<Box<[u8]>>) -> Self {
+        Self {
+            bytes: bytes.into(),
+        }
+    }
+
+    /// Returns the raw bytes of the message.
+    #[inline(always)]
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// Consumes this [`OwnedMessage`], returning its raw bytes.
+    #[inline(always)]
+    pub fn into_bytes(self) -> Box<[u8]> {
+        self.bytes
+    }
+
+    /// Parses the message as a single [`Request`] borrowing from this [`OwnedMessage`].
+    pub fn request<'a, P>(&'a self) -> serde_json::Result<Request<'a, P>>
+    where
+        P: Deserialize<'a>,
+    {
+        serde_json::from_slice(&self.bytes)
+    }
+
+    /// Parses the message as a single request or a batch of requests borrowing from this
+    /// [`OwnedMessage`].
+    pub fn requests<'a, P>(&'a self) -> serde_json::Result<MaybeBatchedRequests<'a, P>>
+    where
+        P: Deserialize<'a>,
+    {
+        serde_json::from_slice(&self.bytes)
+    }
+
+    /// Parses the message as a [`Response`] borrowing from this [`OwnedMessage`].
+    pub fn response<'a, T, E>(&'a self) -> serde_json::Result<Response<'a, T, E>>
+    where
+        T: Deserialize<'a>,
+        E: Deserialize<'a>,
+    {
+        serde_json::from_slice(&self.bytes)
+    }
+}
+
+impl From<Vec<u8>> for OwnedMessage {
+    #[inline(always)]
+    fn from(bytes: Vec<u8>) -> Self {
+        Self::new(bytes)
+    }
+}
+
+impl From<String> for OwnedMessage {
+    #[inline(always)]
+    fn from(s: String) -> Self {
+        Self::new(s.into_bytes())
+    }
+}
+
+#[test]
+#[cfg(test)]
+fn borrowed_views() {
+    let message = OwnedMessage::from(String::from(
+        r#"{"jsonrpc":"2.0","method":"echo","params":["hello"],"id":"a"}"#,
+    ));
+
+    // The message can be moved around, e.g. into another thread.
+    let message = std::thread::spawn(move || message).join().unwrap();
+
+    let request = message.request::<(&str,)>().unwrap();
+    assert!(matches!(request.method, std::borrow::Cow::Borrowed("echo")));
+    assert_eq!(request.params, ("hello",));
+
+    match message.requests::<(&str,)>().unwrap() {
+        MaybeBatchedRequests::Single(request) => assert_eq!(request.params, ("hello",)),
+        MaybeBatchedRequests::Batch(_) => panic!("expected a single request"),
+    }
+}
+
+#[test]
+#[cfg(test)]
+fn batch_and_response_views() {
+    let message = OwnedMessage::from(String::from(
+        r#"[{"jsonrpc":"2.0","method":"a"},{"jsonrpc":"2.0","method":"b","id":1}]"#,
+    ));
+    match message.requests::<Option<()>>().unwrap() {
+        MaybeBatchedRequests::Batch(requests) => assert_eq!(requests.len(), 2),
+        MaybeBatchedRequests::Single(_) => panic!("expected a batch"),
+    }
+    assert!(message.request::<Option<()>>().is_err());
+
+    let message = OwnedMessage::from(String::from(r#"{"jsonrpc":"2.0","result":"ok","id":1}"#));
+    let response = message.response::<&str, ()>().unwrap();
+    assert_eq!(response.result.unwrap(), "ok");
+    assert_eq!(response.id, crate::Id::Uint(1));
+}
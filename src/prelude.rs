@@ -0,0 +1,29 @@
+//! Re-exports the items most commonly needed when writing JSON-RPC 2.0 method handlers.
+//!
+//! ```
+//! use jsonrpc_sys::prelude::*;
+//!
+//! fn divide(a: i64, b: i64) -> RpcResult<i64> {
+//!     if b == 0 {
+//!         return Err(Error {
+//!             code: ErrorCode::INVALID_PARAMS,
+//!             message: "division by zero".into(),
+//!             data: None,
+//!         });
+//!     }
+//!
+//!     Ok(a / b)
+//! }
+//! # assert_eq!(divide(6, 3).unwrap(), 2);
+//! ```
+
+pub use crate::{
+    Error, ErrorCode, Id, IntoRpcError, MaybeBatchedRequests, NoData, NoResult, Request, Response,
+    RpcResult,
+};
+
+#[cfg(feature = "unknown_params")]
+pub use crate::{RawRequest, RawResponse, UnknownParams};
+
+#[cfg(feature = "utils")]
+pub use crate::{read_request, read_response, write_failure, write_response, write_success};
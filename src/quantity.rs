@@ -0,0 +1,208 @@
+//! Serializes unsigned integers as `0x`-prefixed hexadecimal quantities.
+//!
+//! This is a common convention of JSON-RPC APIs, where large integers cannot be represented
+//! exactly by the numbers of some JSON implementations. A quantity is written with the least
+//! number of digits possible, such as `"0x0"` or `"0x1a"`.
+//!
+//! The functions of this module are strict: they reject quantities that are missing their
+//! prefix, that have leading zeros, or that have no digits at all. The [`lenient`] module
+//! accepts all of those, as well as plain JSON numbers.
+//!
+//! ```
+//! # use serde::{Deserialize, Serialize};
+//! #[derive(Serialize, Deserialize)]
+//! struct Block {
+//!     #[serde(with = "jsonrpc_sys::quantity")]
+//!     number: u64,
+//!     #[serde(with = "jsonrpc_sys::quantity::lenient")]
+//!     difficulty: u128,
+//! }
+//! ```
+
+use std::fmt;
+use std::marker::PhantomData;
+
+use serde::de::{Error, Unexpected, Visitor};
+use serde::{Deserializer, Serializer};
+
+/// Serializes the provided integer as a hexadecimal quantity.
+pub fn serialize<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: Copy + TryInto<u128>,
+    S: Serializer,
+{
+    match (*value).try_into() {
+        Ok(value) => serializer.collect_str(&format_args!("{value:#x}")),
+        Err(_) => Err(serde::ser::Error::custom("quantities cannot be negative")),
+    }
+}
+
+/// Deserializes an integer from a hexadecimal quantity.
+pub fn deserialize<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: TryFrom<u128>,
+{
+    deserializer.deserialize_str(QuantityVisitor(false, PhantomData))
+}
+
+/// Lenient variants of the functions of the [`quantity`](self) module.
+pub mod lenient {
+    use super::*;
+
+    /// Serializes the provided integer as a hexadecimal quantity.
+    ///
+    /// This is the same as [`quantity::serialize`](super::serialize).
+    pub fn serialize<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: Copy + TryInto<u128>,
+        S: Serializer,
+    {
+        super::serialize(value, serializer)
+    }
+
+    /// Deserializes an integer from a hexadecimal quantity, or from a JSON number.
+    ///
+    /// The `0x` prefix is optional (and may be uppercase), and leading zeros are ignored.
+    pub fn deserialize<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+    where
+        D: Deserializer<'de>,
+        T: TryFrom<u128>,
+    {
+        deserializer.deserialize_any(QuantityVisitor(true, PhantomData))
+    }
+}
+
+/// Parses a quantity, leniently if `.0` is set.
+struct QuantityVisitor<T>(bool, PhantomData<T>);
+
+impl<T> QuantityVisitor<T>
+where
+    T: TryFrom<u128>,
+{
+    fn convert<E>(&self, value: u128) -> Result<T, E>
+    where
+        E: Error,
+    {
+        T::try_from(value).map_err(|_| {
+            let unexpected = match u64::try_from(value) {
+                Ok(value) => Unexpected::Unsigned(value),
+                Err(_) => Unexpected::Other("a larger integer"),
+            };
+            E::invalid_value(unexpected, self)
+        })
+    }
+}
+
+impl<'de, T> Visitor<'de> for QuantityVisitor<T>
+where
+    T: TryFrom<u128>,
+{
+    type Value = T;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a hexadecimal quantity")
+    }
+
+    fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E>
+    where
+        E: Error,
+    {
+        if !self.0 {
+            return Err(E::invalid_type(Unexpected::Unsigned(v), &self));
+        }
+
+        self.convert(v.into())
+    }
+
+    fn visit_u128<E>(self, v: u128) -> Result<Self::Value, E>
+    where
+        E: Error,
+    {
+        if !self.0 {
+            return Err(E::invalid_type(Unexpected::Other("integer"), &self));
+        }
+
+        self.convert(v)
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: Error,
+    {
+        let invalid = || E::invalid_value(Unexpected::Str(v), &self);
+
+        let digits = match v.strip_prefix("0x") {
+            Some(digits) => digits,
+            None if self.0 => v.strip_prefix("0X").unwrap_or(v),
+            None => return Err(invalid()),
+        };
+
+        if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return Err(invalid());
+        }
+
+        if !self.0 && digits.len() > 1 && digits.starts_with('0') {
+            return Err(invalid());
+        }
+
+        match u128::from_str_radix(digits, 16) {
+            Ok(value) => self.convert(value),
+            Err(_) => Err(E::invalid_value(
+                Unexpected::Other("a larger integer"),
+                &self,
+            )),
+        }
+    }
+}
+
+#[test]
+#[cfg(test)]
+fn quantity() {
+    #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+    struct Strict(#[serde(with = "self")] u64);
+
+    #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+    struct Lenient(#[serde(with = "lenient")] u8);
+
+    assert_eq!(serde_json::to_string(&Strict(0)).unwrap(), r#""0x0""#);
+    assert_eq!(serde_json::to_string(&Strict(26)).unwrap(), r#""0x1a""#);
+    assert_eq!(
+        serde_json::from_str::<Strict>(r#""0x0""#).unwrap(),
+        Strict(0)
+    );
+    assert_eq!(
+        serde_json::from_str::<Strict>(r#""0x1A""#).unwrap(),
+        Strict(26)
+    );
+    for invalid in [
+        r#""0x""#,
+        r#""0x01""#,
+        r#""0x00""#,
+        r#""1a""#,
+        r#""0x+1""#,
+        "26",
+    ] {
+        assert!(
+            serde_json::from_str::<Strict>(invalid).is_err(),
+            "{invalid}"
+        );
+    }
+    assert!(serde_json::from_str::<Strict>(r#""0x10000000000000000""#).is_err());
+
+    assert_eq!(
+        serde_json::from_str::<Lenient>(r#""0x01""#).unwrap(),
+        Lenient(1)
+    );
+    assert_eq!(
+        serde_json::from_str::<Lenient>(r#""0XfF""#).unwrap(),
+        Lenient(255)
+    );
+    assert_eq!(
+        serde_json::from_str::<Lenient>(r#""1a""#).unwrap(),
+        Lenient(26)
+    );
+    assert_eq!(serde_json::from_str::<Lenient>("26").unwrap(), Lenient(26));
+    assert!(serde_json::from_str::<Lenient>(r#""0x100""#).is_err());
+    assert!(serde_json::from_str::<Lenient>(r#""0x""#).is_err());
+}
@@ -0,0 +1,594 @@
+//! Hand-written deserialization of the envelope types.
+//!
+//! Deriving `Deserialize` on intermediate structures works, but keeps the `jsonrpc` member
+//! around as a `Cow<str>` only to check it afterwards, and goes through generic field
+//! identifiers. The visitors of this module validate members as soon as they are read, while
+//! keeping the behavior of the derived implementations:
+//!
+//! - unknown members are ignored;
+//! - members that appear more than once are rejected;
+//! - missing members are deserialized as if they were `null` when their type accepts it (so a
+//!   missing `params` is valid for `Option<_>` parameters, and a missing `id` in a response is
+//!   [`Id::Null`]), and rejected otherwise.
+
+use std::borrow::Cow;
+use std::fmt;
+use std::marker::PhantomData;
+
+use serde::de::{self, Deserialize, Deserializer, IgnoredAny, MapAccess, Visitor};
+
+use crate::{Error, ErrorCode, Id, Request, Response};
+
+/// The members of the objects defined by the specification.
+enum Member {
+    Jsonrpc,
+    Method,
+    Params,
+    Id,
+    Result,
+    Error,
+    Code,
+    Message,
+    Data,
+    Unknown,
+}
+
+impl<'de> Deserialize<'de> for Member {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct MemberVisitor;
+
+        impl<'de> Visitor<'de> for MemberVisitor {
+            type Value = Member;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a member name")
+            }
+
+            fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                Ok(match v {
+                    "jsonrpc" => Member::Jsonrpc,
+                    "method" => Member::Method,
+                    "params" => Member::Params,
+                    "id" => Member::Id,
+                    "result" => Member::Result,
+                    "error" => Member::Error,
+                    "code" => Member::Code,
+                    "message" => Member::Message,
+                    "data" => Member::Data,
+                    _ => Member::Unknown,
+                })
+            }
+        }
+
+        deserializer.deserialize_identifier(MemberVisitor)
+    }
+}
+
+/// The `jsonrpc` member, which must be exactly `"2.0"`.
+struct Version;
+
+impl<'de> Deserialize<'de> for Version {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct VersionVisitor;
+
+        impl<'de> Visitor<'de> for VersionVisitor {
+            type Value = Version;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a string")
+            }
+
+            fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                if v == "2.0" {
+                    Ok(Version)
+                } else {
+                    Err(E::invalid_value(de::Unexpected::Str(v), &"2.0"))
+                }
+            }
+        }
+
+        deserializer.deserialize_str(VersionVisitor)
+    }
+}
+
+/// A string that borrows from the input when possible.
+struct CowStr<'a>(Cow<'a, str>);
+
+impl<'de: 'a, 'a> Deserialize<'de> for CowStr<'a> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct CowStrVisitor;
+
+        impl<'de> Visitor<'de> for CowStrVisitor {
+            type Value = CowStr<'de>;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a string")
+            }
+
+            fn visit_borrowed_str<E>(self, v: &'de str) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                Ok(CowStr(Cow::Borrowed(v)))
+            }
+
+            fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                Ok(CowStr(Cow::Owned(v.to_owned())))
+            }
+
+            fn visit_string<E>(self, v: String) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                Ok(CowStr(Cow::Owned(v)))
+            }
+        }
+
+        deserializer.deserialize_str(CowStrVisitor)
+    }
+}
+
+/// A [`Deserializer`] standing for a missing member.
+///
+/// It produces `None` when an option is requested, and a "missing field" error otherwise.
+struct Missing<E>(&'static str, PhantomData<E>);
+
+impl<'de, E> Deserializer<'de> for Missing<E>
+where
+    E: de::Error,
+{
+    type Error = E;
+
+    fn deserialize_any<V>(self, _: V) -> Result<V::Value, E>
+    where
+        V: Visitor<'de>,
+    {
+        Err(E::missing_field(self.0))
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, E>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_none()
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct enum identifier ignored_any
+    }
+}
+
+/// Returns the value of a member, or deserializes it from [`Missing`] if it was not present.
+fn or_missing<'de, T, E>(value: Option<T>, name: &'static str) -> Result<T, E>
+where
+    T: Deserialize<'de>,
+    E: de::Error,
+{
+    match value {
+        Some(value) => Ok(value),
+        None => T::deserialize(Missing(name, PhantomData)),
+    }
+}
+
+/// Stores the value of a member, failing if it was already present.
+fn set<T, E>(slot: &mut Option<T>, value: T, name: &'static str) -> Result<(), E>
+where
+    E: de::Error,
+{
+    if slot.is_some() {
+        return Err(E::duplicate_field(name));
+    }
+    *slot = Some(value);
+    Ok(())
+}
+
+/// Deserializes a [`Request`].
+pub(crate) fn deserialize_request<'de, 'a, D, P>(
+    deserializer: D,
+) -> Result<Request<'a, P>, D::Error>
+where
+    'de: 'a,
+    D: Deserializer<'de>,
+    P: Deserialize<'de>,
+{
+    struct RequestVisitor<P>(PhantomData<P>);
+
+    impl<'de, P> Visitor<'de> for RequestVisitor<P>
+    where
+        P: Deserialize<'de>,
+    {
+        type Value = Request<'de, P>;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("a JSON-RPC 2.0 request")
+        }
+
+        fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+        where
+            A: MapAccess<'de>,
+        {
+            let mut jsonrpc = None;
+            let mut method = None;
+            let mut params = None;
+            let mut id = None;
+
+            while let Some(member) = map.next_key()? {
+                match member {
+                    Member::Jsonrpc => set(&mut jsonrpc, map.next_value::<Version>()?, "jsonrpc")?,
+                    Member::Method => set(&mut method, map.next_value::<CowStr>()?, "method")?,
+                    Member::Params => set(&mut params, map.next_value()?, "params")?,
+                    // `Id` deserializes `null` as `Id::Null`, distinguishing it from a missing
+                    // `id` member.
+                    Member::Id => set(&mut id, map.next_value::<Id>()?, "id")?,
+                    _ => drop(map.next_value::<IgnoredAny>()?),
+                }
+            }
+
+            or_missing::<Version, _>(jsonrpc, "jsonrpc")?;
+            let method = or_missing::<CowStr, _>(method, "method")?.0;
+            let params = or_missing(params, "params")?;
+
+            Ok(Request { method, params, id })
+        }
+    }
+
+    const MEMBERS: &[&str] = &["jsonrpc", "method", "params", "id"];
+    deserializer.deserialize_struct("Request", MEMBERS, RequestVisitor(PhantomData))
+}
+
+/// Deserializes a [`Response`].
+pub(crate) fn deserialize_response<'de, 'a, D, T, E>(
+    deserializer: D,
+) -> Result<Response<'a, T, E>, D::Error>
+where
+    'de: 'a,
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+    E: Deserialize<'de>,
+{
+    struct ResponseVisitor<T, E>(PhantomData<(T, E)>);
+
+    impl<'de, T, E> Visitor<'de> for ResponseVisitor<T, E>
+    where
+        T: Deserialize<'de>,
+        E: Deserialize<'de>,
+    {
+        type Value = Response<'de, T, E>;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("a JSON-RPC 2.0 response")
+        }
+
+        fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+        where
+            A: MapAccess<'de>,
+        {
+            let mut jsonrpc = None;
+            let mut result = None;
+            let mut error = None;
+            let mut id = None;
+
+            while let Some(member) = map.next_key()? {
+                match member {
+                    Member::Jsonrpc => set(&mut jsonrpc, map.next_value::<Version>()?, "jsonrpc")?,
+                    Member::Result => set(&mut result, map.next_value()?, "result")?,
+                    // A `null` error is treated as a missing one.
+                    Member::Error => set(
+                        &mut error,
+                        map.next_value::<Option<IncomingError<E>>>()?,
+                        "error",
+                    )?,
+                    Member::Id => set(&mut id, map.next_value()?, "id")?,
+                    _ => drop(map.next_value::<IgnoredAny>()?),
+                }
+            }
+
+            or_missing::<Version, _>(jsonrpc, "jsonrpc")?;
+            let id = or_missing::<Id, _>(id, "id")?;
+
+            let result = match (result, error.flatten()) {
+                (Some(IncomingResult::Value(result)), None) => Ok(result),
+                (Some(IncomingResult::Null(result)), None) => {
+                    Ok(result.map_err(de::Error::custom)?)
+                }
+                // Some servers send `"result": null` alongside the error.
+                (None | Some(IncomingResult::Null(_)), Some(error)) => Err(error.0),
+                (Some(IncomingResult::Value(_)), Some(_)) => {
+                    return Err(de::Error::custom(
+                        "response cannot contain both `result` and `error` fields",
+                    ))
+                }
+                (None, None) => {
+                    return Err(de::Error::custom(
+                        "response must contain either `result` or `error` field",
+                    ))
+                }
+            };
+
+            Ok(Response { result, id })
+        }
+    }
+
+    const MEMBERS: &[&str] = &["jsonrpc", "result", "error", "id"];
+    deserializer.deserialize_struct("Response", MEMBERS, ResponseVisitor(PhantomData))
+}
+
+/// The `result` member of a response.
+enum IncomingResult<T> {
+    /// The member was not `null`.
+    Value(T),
+    /// The member was `null`.
+    ///
+    /// This contains the result of deserializing `T` from `null`. It is only an error if the
+    /// response turns out not to contain an `error` member.
+    Null(Result<T, de::value::Error>),
+}
+
+impl<'de, T> Deserialize<'de> for IncomingResult<T>
+where
+    T: Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct ResultVisitor<T>(PhantomData<T>);
+
+        impl<'de, T> Visitor<'de> for ResultVisitor<T>
+        where
+            T: Deserialize<'de>,
+        {
+            type Value = IncomingResult<T>;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a JSON-RPC 2.0 result")
+            }
+
+            fn visit_none<E>(self) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                Ok(IncomingResult::Null(T::deserialize(
+                    de::value::UnitDeserializer::new(),
+                )))
+            }
+
+            fn visit_unit<E>(self) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                self.visit_none()
+            }
+
+            fn visit_some<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+            where
+                D: Deserializer<'de>,
+            {
+                T::deserialize(EmptyAsUnit(deserializer)).map(IncomingResult::Value)
+            }
+        }
+
+        deserializer.deserialize_option(ResultVisitor(PhantomData))
+    }
+}
+
+/// A deserializer that accepts an empty object where a unit is expected.
+///
+/// Some servers acknowledge methods that have nothing to return with `"result": {}`, which
+/// this allows to read as a `()` result.
+pub(crate) struct EmptyAsUnit<D>(pub(crate) D);
+
+macro_rules! forward {
+    ($($name:ident($($arg:ident: $ty:ty),*);)*) => {
+        $(
+            #[inline(always)]
+            fn $name<V>(self, $($arg: $ty,)* visitor: V) -> Result<V::Value, D::Error>
+            where
+                V: Visitor<'de>,
+            {
+                self.0.$name($($arg,)* visitor)
+            }
+        )*
+    };
+}
+
+impl<'de, D> Deserializer<'de> for EmptyAsUnit<D>
+where
+    D: Deserializer<'de>,
+{
+    type Error = D::Error;
+
+    fn deserialize_unit<V>(self, visitor: V) -> Result<V::Value, D::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.0.deserialize_any(UnitVisitor(visitor))
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, D::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.0.deserialize_option(OptionVisitor(visitor))
+    }
+
+    forward! {
+        deserialize_any();
+        deserialize_bool();
+        deserialize_i8();
+        deserialize_i16();
+        deserialize_i32();
+        deserialize_i64();
+        deserialize_i128();
+        deserialize_u8();
+        deserialize_u16();
+        deserialize_u32();
+        deserialize_u64();
+        deserialize_u128();
+        deserialize_f32();
+        deserialize_f64();
+        deserialize_char();
+        deserialize_str();
+        deserialize_string();
+        deserialize_bytes();
+        deserialize_byte_buf();
+        deserialize_unit_struct(name: &'static str);
+        deserialize_newtype_struct(name: &'static str);
+        deserialize_seq();
+        deserialize_tuple(len: usize);
+        deserialize_tuple_struct(name: &'static str, len: usize);
+        deserialize_map();
+        deserialize_struct(name: &'static str, fields: &'static [&'static str]);
+        deserialize_enum(name: &'static str, variants: &'static [&'static str]);
+        deserialize_identifier();
+        deserialize_ignored_any();
+    }
+
+    #[inline(always)]
+    fn is_human_readable(&self) -> bool {
+        self.0.is_human_readable()
+    }
+}
+
+/// Visits a unit or an empty map as a unit.
+struct UnitVisitor<V>(V);
+
+impl<'de, V> Visitor<'de> for UnitVisitor<V>
+where
+    V: Visitor<'de>,
+{
+    type Value = V::Value;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("null or an empty object")
+    }
+
+    fn visit_unit<E>(self) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        self.0.visit_unit()
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        if map.next_key::<IgnoredAny>()?.is_some() {
+            return Err(de::Error::invalid_length(1, &self));
+        }
+        self.0.visit_unit()
+    }
+}
+
+/// Visits an option, deserializing its value through [`EmptyAsUnit`], so that `Option<()>`
+/// reads an empty object as `Some(())`.
+struct OptionVisitor<V>(V);
+
+impl<'de, V> Visitor<'de> for OptionVisitor<V>
+where
+    V: Visitor<'de>,
+{
+    type Value = V::Value;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        self.0.expecting(formatter)
+    }
+
+    fn visit_none<E>(self) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        self.0.visit_none()
+    }
+
+    fn visit_unit<E>(self) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        self.0.visit_unit()
+    }
+
+    fn visit_some<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        self.0.visit_some(EmptyAsUnit(deserializer))
+    }
+}
+
+/// The `error` member of a response.
+struct IncomingError<'a, E>(Error<'a, E>);
+
+impl<'de: 'a, 'a, E> Deserialize<'de> for IncomingError<'a, E>
+where
+    E: Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct ErrorVisitor<E>(PhantomData<E>);
+
+        impl<'de, E> Visitor<'de> for ErrorVisitor<E>
+        where
+            E: Deserialize<'de>,
+        {
+            type Value = IncomingError<'de, E>;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a JSON-RPC 2.0 error")
+            }
+
+            fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+            where
+                A: MapAccess<'de>,
+            {
+                let mut code = None;
+                let mut message = None;
+                let mut data = None;
+
+                while let Some(member) = map.next_key()? {
+                    match member {
+                        Member::Code => set(&mut code, map.next_value::<i64>()?, "code")?,
+                        Member::Message => {
+                            set(&mut message, map.next_value::<CowStr>()?, "message")?
+                        }
+                        // A `null` data is treated as a missing one.
+                        Member::Data => set(&mut data, map.next_value::<Option<E>>()?, "data")?,
+                        _ => drop(map.next_value::<IgnoredAny>()?),
+                    }
+                }
+
+                Ok(IncomingError(Error {
+                    code: ErrorCode(or_missing(code, "code")?),
+                    message: or_missing::<CowStr, _>(message, "message")?.0,
+                    data: data.flatten(),
+                }))
+            }
+        }
+
+        const MEMBERS: &[&str] = &["code", "message", "data"];
+        deserializer.deserialize_struct("Error", MEMBERS, ErrorVisitor(PhantomData))
+    }
+}
@@ -0,0 +1,128 @@
+use std::borrow::Cow;
+use std::collections::HashSet;
+use std::fmt;
+
+use crate::Request;
+
+/// A policy controlling which request parameters are hidden from [`Debug`] output.
+///
+/// Logging requests with `{:?}` is convenient, but can dump credentials or other secrets passed
+/// as parameters. Wrapping requests with [`Redaction::request`] before formatting them hides the
+/// parameters of sensitive methods entirely, and truncates the parameters of other methods
+/// past a configurable length.
+///
+/// # Examples
+///
+/// ```
+/// use std::borrow::Cow;
+///
+/// use jsonrpc_sys::{Redaction, Request};
+///
+/// let redaction = Redaction::new().method("login").max_len(16);
+/// let request = Request {
+///     method: Cow::Borrowed("login"),
+///     params: ("admin", "hunter2"),
+///     id: None,
+/// };
+///
+/// let output = format!("{:?}", redaction.request(&request));
+/// assert!(!output.contains("hunter2"));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Redaction {
+    /// The methods whose parameters must be hidden entirely.
+    methods: HashSet<Cow<'static, str>>,
+    /// The maximum length of the formatted parameters of other methods.
+    max_len: Option<usize>,
+}
+
+impl Redaction {
+    /// Creates a new [`Redaction`] policy that hides nothing.
+    #[inline(always)]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Hides the parameters of the provided method entirely.
+    pub fn method(mut self, name: impl Into<Cow<'static, str>>) -> Self {
+        self.methods.insert(name.into());
+        self
+    }
+
+    /// Truncates the formatted parameters of other methods to `max_len` bytes.
+    #[inline(always)]
+    pub fn max_len(mut self, max_len: usize) -> Self {
+        self.max_len = Some(max_len);
+        self
+    }
+
+    /// Wraps the provided request so that its [`Debug`] output follows this policy.
+    #[inline(always)]
+    pub fn request<'r, 'a, P>(&'r self, request: &'r Request<'a, P>) -> RedactedRequest<'r, 'a, P> {
+        RedactedRequest {
+            redaction: self,
+            request,
+        }
+    }
+}
+
+/// A [`Request`] whose [`Debug`] output follows a [`Redaction`] policy.
+///
+/// This type is returned by [`Redaction::request`].
+pub struct RedactedRequest<'r, 'a, P> {
+    redaction: &'r Redaction,
+    request: &'r Request<'a, P>,
+}
+
+impl<'r, 'a, P> fmt::Debug for RedactedRequest<'r, 'a, P>
+where
+    P: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        /// Formats a string without quotes.
+        struct Verbatim<'s>(&'s str);
+
+        impl fmt::Debug for Verbatim<'_> {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str(self.0)
+            }
+        }
+
+        let mut s = f.debug_struct("Request");
+        s.field("method", &self.request.method);
+
+        if self.redaction.methods.contains(&*self.request.method) {
+            s.field("params", &Verbatim("<redacted>"));
+        } else if let Some(max_len) = self.redaction.max_len {
+            let mut params = format!("{:?}", self.request.params);
+            if params.len() > max_len {
+                let mut end = max_len;
+                while !params.is_char_boundary(end) {
+                    end -= 1;
+                }
+                params.truncate(end);
+                params.push_str("...");
+            }
+            s.field("params", &Verbatim(&params));
+        } else {
+            s.field("params", &self.request.params);
+        }
+
+        s.field("id", &self.request.id).finish()
+    }
+}
+
+#[test]
+#[cfg(test)]
+fn truncate() {
+    let redaction = Redaction::new().max_len(4);
+    let request = Request {
+        method: Cow::Borrowed("m"),
+        params: "abcdef",
+        id: None,
+    };
+    assert_eq!(
+        format!("{:?}", redaction.request(&request)),
+        r#"Request { method: "m", params: "abc..., id: None }"#
+    );
+}
@@ -5,6 +5,10 @@ use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use crate::Id;
 
 /// A JSON-RPC 2.0 request.
+///
+/// Deserializing a request that contains the same member more than once (for example, two
+/// `id` members) fails instead of silently picking one of them, as parsers disagreeing on
+/// which one to keep can be used to smuggle requests through proxies.
 #[derive(Debug, Clone)]
 pub struct Request<'a, P> {
     /// The method to be invoked.
@@ -15,6 +19,32 @@ pub struct Request<'a, P> {
     pub id: Option<Id<'a>>,
 }
 
+impl<'a, P> Request<'a, P> {
+    /// Returns an estimate of the number of bytes taken by this request once serialized to
+    /// JSON, *excluding* its parameters.
+    ///
+    /// This can be used as a lower bound when pre-sizing output buffers. Escape sequences in
+    /// the method name are not accounted for.
+    pub fn size_hint(&self) -> usize {
+        // {"jsonrpc":"2.0","method":"","params":}
+        let mut size = 39 + self.method.len();
+        if let Some(ref id) = self.id {
+            // ,"id":
+            size += 6 + id.size_hint();
+        }
+        size
+    }
+
+    /// Converts this [`Request`] into one that owns its method name and identifier.
+    pub fn into_owned(self) -> Request<'static, P> {
+        Request {
+            method: Cow::Owned(self.method.into_owned()),
+            params: self.params,
+            id: self.id.map(Id::into_owned),
+        }
+    }
+}
+
 impl<'a, P> Serialize for Request<'a, P>
 where
     P: Serialize,
@@ -36,7 +66,7 @@ where
     where
         D: Deserializer<'de>,
     {
-        IncomingRequest::deserialize(deserializer).and_then(IncomingRequest::into_request)
+        crate::raw::deserialize_request(deserializer)
     }
 }
 
@@ -60,54 +90,6 @@ impl<'a, P> OutgoingRequest<'a, P> {
     }
 }
 
-#[derive(Deserialize)]
-struct IncomingRequest<'a, P> {
-    #[serde(borrow)]
-    jsonrpc: Cow<'a, str>,
-    #[serde(borrow)]
-    method: Cow<'a, str>,
-    params: P,
-    #[serde(borrow, default, deserialize_with = "deserialize_id")]
-    id: Option<crate::Id<'a>>,
-}
-
-impl<'a, P> IncomingRequest<'a, P> {
-    fn into_request<E>(self) -> Result<crate::Request<'a, P>, E>
-    where
-        E: serde::de::Error,
-        P: serde::Deserialize<'a>,
-    {
-        if self.jsonrpc != "2.0" {
-            return Err(E::invalid_value(
-                serde::de::Unexpected::Str(&self.jsonrpc),
-                &"2.0",
-            ));
-        }
-
-        Ok(crate::Request {
-            method: self.method,
-            params: self.params,
-            id: self.id,
-        })
-    }
-}
-
-/// Deserializes an `Id` from a JSON-RPC 2.0 request.
-///
-/// This deserialization function is useful to distinguish between a null id and no id
-/// specified in a request.
-fn deserialize_id<'de, D>(deserializer: D) -> Result<Option<crate::Id<'de>>, D::Error>
-where
-    D: Deserializer<'de>,
-{
-    let opt = Option::deserialize(deserializer)?;
-
-    match opt {
-        Some(some) => Ok(some),
-        None => Ok(Some(Id::Null)),
-    }
-}
-
 #[test]
 #[cfg(test)]
 fn null_id() {
@@ -123,3 +105,37 @@ fn no_id() {
     let request: crate::Request<'_, Option<()>> = serde_json::from_str(request).unwrap();
     assert_eq!(request.id, None);
 }
+
+#[test]
+#[cfg(test)]
+fn size_hint() {
+    let request = crate::Request {
+        method: Cow::Borrowed("subtract"),
+        params: (),
+        id: Some(Id::Uint(42)),
+    };
+    let json = serde_json::to_string(&request).unwrap();
+    assert_eq!(request.size_hint(), json.len() - "null".len());
+}
+
+#[test]
+#[cfg(test)]
+fn size_hint_lower_bound() {
+    // Parameters that serialize as `null` are still written as a `params` member, which the
+    // hint accounts for.
+    let request = crate::Request {
+        method: Cow::Borrowed("m"),
+        params: (),
+        id: None,
+    };
+    let json = serde_json::to_vec(&request).unwrap();
+    assert_eq!(json, br#"{"jsonrpc":"2.0","method":"m","params":null}"#);
+    assert!(request.size_hint() <= json.len());
+}
+
+#[test]
+#[cfg(test)]
+fn duplicate_members() {
+    let request = r#"{"jsonrpc":"2.0","method":"","id":1,"id":2}"#;
+    assert!(serde_json::from_str::<crate::Request<'_, Option<()>>>(request).is_err());
+}
@@ -5,6 +5,15 @@ use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use crate::Id;
 
 /// A JSON-RPC 2.0 response.
+///
+/// Like [`Request`], deserializing a response that contains the same member more than once,
+/// including inside of its `error` member, fails.
+///
+/// A `()` result can be read from both `"result": null` and `"result": {}`, as servers
+/// disagree on how to acknowledge methods that have nothing to return. An `Option<()>` result
+/// reads `"result": {}` as `Some(())`.
+///
+/// [`Request`]: crate::Request
 #[derive(Debug, Clone)]
 pub struct Response<'a, T, E> {
     /// The outcome of the request.
@@ -36,7 +45,71 @@ where
     where
         D: Deserializer<'de>,
     {
-        IncomingResponse::deserialize(deserializer).and_then(IncomingResponse::into_response)
+        crate::raw::deserialize_response(deserializer)
+    }
+}
+
+impl<'a, T, E> Response<'a, T, E> {
+    /// Returns an estimate of the number of bytes taken by this response once serialized to
+    /// JSON, *excluding* its result or error data.
+    ///
+    /// This can be used as a lower bound when pre-sizing output buffers. Escape sequences in
+    /// the error message are not accounted for.
+    pub fn size_hint(&self) -> usize {
+        // {"jsonrpc":"2.0",,"id":}
+        let mut size = 24 + self.id.size_hint();
+        match self.result {
+            // "result":
+            Ok(_) => size += 9,
+            Err(ref error) => {
+                // "error":{"code":,"message":""}
+                size += 30 + error.message.len();
+                size += Id::Int(error.code.0).size_hint();
+                if error.data.is_some() {
+                    // ,"data":
+                    size += 8;
+                }
+            }
+        }
+        size
+    }
+
+    /// Creates an [`ErrorCode::PARSE_ERROR`] response.
+    ///
+    /// The specification requires the ID of such responses to be `null`, as the ID of a request
+    /// that could not be parsed is unknown.
+    pub fn parse_error(message: impl Into<Cow<'a, str>>) -> Self {
+        Self {
+            result: Err(Error {
+                code: ErrorCode::PARSE_ERROR,
+                message: message.into(),
+                data: None,
+            }),
+            id: Id::Null,
+        }
+    }
+
+    /// Creates an [`ErrorCode::INVALID_REQUEST`] response for a request whose ID could not be
+    /// determined.
+    ///
+    /// Like [`Response::parse_error`], the ID of the response is `null`.
+    pub fn invalid_request(message: impl Into<Cow<'a, str>>) -> Self {
+        Self {
+            result: Err(Error {
+                code: ErrorCode::INVALID_REQUEST,
+                message: message.into(),
+                data: None,
+            }),
+            id: Id::Null,
+        }
+    }
+
+    /// Converts this [`Response`] into one that owns its identifier and error message.
+    pub fn into_owned(self) -> Response<'static, T, E> {
+        Response {
+            result: self.result.map_err(Error::into_owned),
+            id: self.id.into_owned(),
+        }
     }
 }
 
@@ -55,6 +128,39 @@ impl ErrorCode {
     pub const INVALID_PARAMS: ErrorCode = ErrorCode(-32602);
     /// The error code returned when an internal error occurs.
     pub const INTERNAL_ERROR: ErrorCode = ErrorCode(-32603);
+
+    /// Creates an implementation-defined server error code.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `code` is not in the `-32099..=-32000` range reserved for server errors. When
+    /// used in a constant, this is reported at compile time.
+    ///
+    /// ```
+    /// # use jsonrpc_sys::ErrorCode;
+    /// const RATE_LIMITED: ErrorCode = ErrorCode::server_error(-32005);
+    /// ```
+    ///
+    /// ```compile_fail
+    /// # use jsonrpc_sys::ErrorCode;
+    /// const RATE_LIMITED: ErrorCode = ErrorCode::server_error(-32100);
+    /// ```
+    pub const fn server_error(code: i64) -> Self {
+        assert!(
+            -32099 <= code && code <= -32000,
+            "server error codes must be in the -32099..=-32000 range",
+        );
+        Self(code)
+    }
+
+    /// Creates an application-defined error code.
+    ///
+    /// The code is not checked. Codes in the `-32768..=-32000` range are reserved by the
+    /// specification and should not be used by applications.
+    #[inline(always)]
+    pub const fn application(code: i64) -> Self {
+        Self(code)
+    }
 }
 
 impl From<i64> for ErrorCode {
@@ -79,9 +185,139 @@ pub struct Error<'a, E> {
     /// The error message.
     pub message: Cow<'a, str>,
     /// Additional data about the error.
+    ///
+    /// When this is `None`, the `data` member is omitted from the serialized error. Peers that
+    /// require the member to be present can be given an explicit `null` by using `Some(())`
+    /// (or `Some(None)` when `E` is an `Option`).
     pub data: Option<E>,
 }
 
+impl<'a, E> Error<'a, E> {
+    /// Converts this [`Error`] into one that owns its message.
+    pub fn into_owned(self) -> Error<'static, E> {
+        Error {
+            code: self.code,
+            message: Cow::Owned(self.message.into_owned()),
+            data: self.data,
+        }
+    }
+}
+
+/// The result of a method handler that fails with a JSON-RPC 2.0 [`Error`].
+pub type RpcResult<T, E = NoData> = Result<T, Error<'static, E>>;
+
+/// The type of the data of an error that never carries any.
+///
+/// This type has no values: an [`Error<NoData>`] always has its `data` set to `None`, which
+/// is serialized by omitting the `data` member. Deserializing an error whose `data` is
+/// anything other than missing or `null` fails.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum NoData {}
+
+impl Serialize for NoData {
+    #[inline(always)]
+    fn serialize<S>(&self, _: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match *self {}
+    }
+}
+
+impl<'de> Deserialize<'de> for NoData {
+    fn deserialize<D>(_: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Err(serde::de::Error::custom("expected no error data"))
+    }
+}
+
+/// The type of the result of a response that is never successful.
+///
+/// This type has no values: a [`Response<NoResult, E>`] is always an error response.
+/// Deserializing a successful response with this result type fails.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum NoResult {}
+
+impl Serialize for NoResult {
+    #[inline(always)]
+    fn serialize<S>(&self, _: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match *self {}
+    }
+}
+
+impl<'de> Deserialize<'de> for NoResult {
+    fn deserialize<D>(_: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Err(serde::de::Error::custom("expected an error response"))
+    }
+}
+
+#[cfg(any(feature = "utils", feature = "unknown_params"))]
+impl<E> Error<'static, E> {
+    /// Creates an [`ErrorCode::INVALID_PARAMS`] error from an error that occurred while
+    /// deserializing the parameters of a request.
+    pub fn invalid_params(error: serde_json::Error) -> Self {
+        Self {
+            code: ErrorCode::INVALID_PARAMS,
+            message: Cow::Owned(error.to_string()),
+            data: None,
+        }
+    }
+}
+
+/// A type that can be converted into a JSON-RPC 2.0 [`Error`].
+///
+/// Implementing this trait for application error types gives a single place where the error
+/// code, message and data associated with each error are decided.
+pub trait IntoRpcError {
+    /// The type of the additional data attached to the error.
+    type Data;
+
+    /// Converts this value into a JSON-RPC 2.0 [`Error`].
+    fn into_rpc_error(self) -> Error<'static, Self::Data>;
+}
+
+impl<E> IntoRpcError for Error<'static, E> {
+    type Data = E;
+
+    #[inline(always)]
+    fn into_rpc_error(self) -> Error<'static, E> {
+        self
+    }
+}
+
+/// Converts an [`anyhow::Error`] into an [`ErrorCode::INTERNAL_ERROR`].
+///
+/// The message of the error is its top-level message, and the data is the list of messages of
+/// the error and its whole chain of sources.
+#[cfg(feature = "anyhow")]
+impl From<anyhow::Error> for Error<'static, Vec<String>> {
+    fn from(error: anyhow::Error) -> Self {
+        Self {
+            code: ErrorCode::INTERNAL_ERROR,
+            message: Cow::Owned(error.to_string()),
+            data: Some(error.chain().map(ToString::to_string).collect()),
+        }
+    }
+}
+
+#[cfg(feature = "anyhow")]
+impl IntoRpcError for anyhow::Error {
+    type Data = Vec<String>;
+
+    #[inline(always)]
+    fn into_rpc_error(self) -> Error<'static, Vec<String>> {
+        self.into()
+    }
+}
+
 #[derive(Serialize)]
 struct OutogingResponse<'a, T, E> {
     jsonrpc: &'a str,
@@ -123,73 +359,138 @@ struct OutgoingError<'a, E> {
     data: Option<&'a E>,
 }
 
-#[derive(Deserialize)]
-struct IncomingResponse<'a, T, E> {
-    #[serde(borrow)]
-    jsonrpc: Cow<'a, str>,
-    #[serde(default = "Option::default")]
-    result: Option<T>,
-    #[serde(default = "Option::default", borrow)]
-    error: Option<IncomingError<'a, E>>,
-    // The option is there to represent the `null` value. The `id` field must still be
-    // present.
-    #[serde(borrow)]
-    id: crate::Id<'a>,
+fn id_as_ref<'a>(id: &'a crate::Id) -> crate::Id<'a> {
+    match *id {
+        crate::Id::Null => crate::Id::Null,
+        crate::Id::Float(f) => crate::Id::Float(f),
+        crate::Id::Str(ref s) => crate::Id::Str(Cow::Borrowed(s)),
+        crate::Id::Int(i) => crate::Id::Int(i),
+        crate::Id::Uint(u) => crate::Id::Uint(u),
+        crate::Id::Int128(i) => crate::Id::Int128(i),
+        crate::Id::Uint128(u) => crate::Id::Uint128(u),
+        #[cfg(feature = "arbitrary_precision")]
+        crate::Id::Number(ref n) => crate::Id::Number(n.clone()),
+    }
 }
 
-impl<'a, T, E> IncomingResponse<'a, T, E> {
-    fn into_response<Er>(self) -> Result<crate::Response<'a, T, E>, Er>
-    where
-        Er: serde::de::Error,
-    {
-        if self.jsonrpc != "2.0" {
-            return Err(Er::invalid_value(
-                serde::de::Unexpected::Str(&self.jsonrpc),
-                &"2.0",
-            ));
-        }
+#[test]
+#[cfg(all(test, feature = "anyhow"))]
+fn anyhow_chain() {
+    let error = anyhow::anyhow!("disk full").context("failed to save");
+    let error = error.into_rpc_error();
+    assert_eq!(error.code, ErrorCode::INTERNAL_ERROR);
+    assert_eq!(error.message, "failed to save");
+    assert_eq!(error.data.unwrap(), ["failed to save", "disk full"]);
+}
 
-        let result = match (self.result, self.error) {
-            (Some(result), None) => Ok(result),
-            (None, Some(error)) => Err(crate::Error {
-                code: crate::ErrorCode(error.code),
-                message: error.message,
-                data: error.data,
-            }),
-            (Some(_), Some(_)) => {
-                return Err(Er::custom(
-                    "response cannot contain both `result` and `error` fields",
-                ))
-            }
-            (None, None) => {
-                return Err(Er::custom(
-                    "response must contain either `result` or `error` field",
-                ))
-            }
-        };
+#[test]
+#[cfg(test)]
+fn null_result() {
+    let response = r#"{"jsonrpc":"2.0","result":null,"id":1}"#;
+    let response: Response<Option<i32>, ()> = serde_json::from_str(response).unwrap();
+    assert_eq!(response.result.unwrap(), None);
 
-        Ok(crate::Response {
-            result,
-            id: self.id,
-        })
+    let response = r#"{"jsonrpc":"2.0","result":null,"id":1}"#;
+    assert!(serde_json::from_str::<Response<i32, ()>>(response).is_err());
+
+    let response = r#"{"jsonrpc":"2.0","result":null,"error":{"code":1,"message":""},"id":1}"#;
+    let response: Response<i32, ()> = serde_json::from_str(response).unwrap();
+    assert_eq!(response.result.unwrap_err().code, ErrorCode(1));
+}
+
+#[test]
+#[cfg(test)]
+fn null_data() {
+    let mut response = Response::<(), ()>::parse_error("");
+    assert!(!serde_json::to_string(&response).unwrap().contains("data"));
+    if let Err(ref mut error) = response.result {
+        error.data = Some(());
     }
+    assert!(serde_json::to_string(&response)
+        .unwrap()
+        .contains(r#""data":null"#));
 }
 
-#[derive(Deserialize)]
-struct IncomingError<'a, E> {
-    code: i64,
-    #[serde(borrow)]
-    message: Cow<'a, str>,
-    #[serde(default = "Option::default")]
-    data: Option<E>,
+#[test]
+#[cfg(test)]
+fn size_hint() {
+    let response = Response::<(), ()>::parse_error("Parse error");
+    let json = serde_json::to_string(&response).unwrap();
+    assert_eq!(response.size_hint(), json.len());
 }
 
-fn id_as_ref<'a>(id: &'a crate::Id) -> crate::Id<'a> {
-    match *id {
-        crate::Id::Null => crate::Id::Null,
-        crate::Id::Float(f) => crate::Id::Float(f),
-        crate::Id::Str(ref s) => crate::Id::Str(Cow::Borrowed(s)),
-        crate::Id::Int(i) => crate::Id::Int(i),
-        crate::Id::Uint(u) => crate::Id::Uint(u),
+#[test]
+#[cfg(test)]
+fn duplicate_members() {
+    let response = r#"{"jsonrpc":"2.0","result":1,"id":1,"id":2}"#;
+    assert!(serde_json::from_str::<Response<i32, ()>>(response).is_err());
+    let response = r#"{"jsonrpc":"2.0","error":{"code":1,"code":2,"message":""},"id":1}"#;
+    assert!(serde_json::from_str::<Response<i32, ()>>(response).is_err());
+}
+
+#[test]
+#[cfg(test)]
+fn unit_result() {
+    for input in [
+        r#"{"jsonrpc":"2.0","result":null,"id":1}"#,
+        r#"{"jsonrpc":"2.0","result":{},"id":1}"#,
+        r#"{"jsonrpc":"2.0","result":{ },"id":1}"#,
+    ] {
+        let response: Response<(), ()> = serde_json::from_str(input).unwrap();
+        assert!(response.result.is_ok(), "{input}");
+    }
+
+    for input in [
+        r#"{"jsonrpc":"2.0","result":{"a":1},"id":1}"#,
+        r#"{"jsonrpc":"2.0","result":[],"id":1}"#,
+        r#"{"jsonrpc":"2.0","id":1}"#,
+    ] {
+        assert!(
+            serde_json::from_str::<Response<(), ()>>(input).is_err(),
+            "{input}"
+        );
     }
+
+    // Optional unit results read an empty object as `Some(())`.
+    let response: Response<Option<()>, ()> =
+        serde_json::from_str(r#"{"jsonrpc":"2.0","result":{},"id":1}"#).unwrap();
+    assert_eq!(response.result.unwrap(), Some(()));
+    let response: Response<Option<()>, ()> =
+        serde_json::from_str(r#"{"jsonrpc":"2.0","result":null,"id":1}"#).unwrap();
+    assert_eq!(response.result.unwrap(), None);
+
+    // Only unit results are affected.
+    let response: Response<std::collections::HashMap<String, u32>, ()> =
+        serde_json::from_str(r#"{"jsonrpc":"2.0","result":{},"id":1}"#).unwrap();
+    assert!(response.result.unwrap().is_empty());
+}
+
+#[test]
+#[cfg(test)]
+fn no_data() {
+    let response: Response<(), NoData> = serde_json::from_str(
+        r#"{"jsonrpc":"2.0","error":{"code":1,"message":"m","data":null},"id":1}"#,
+    )
+    .unwrap();
+    assert!(response.result.unwrap_err().data.is_none());
+    assert!(serde_json::from_str::<Response<(), NoData>>(
+        r#"{"jsonrpc":"2.0","error":{"code":1,"message":"m","data":1},"id":1}"#
+    )
+    .is_err());
+
+    let response: Response<NoResult, NoData> =
+        serde_json::from_str(r#"{"jsonrpc":"2.0","error":{"code":1,"message":"m"},"id":1}"#)
+            .unwrap();
+    assert!(response.result.is_err());
+    assert!(serde_json::from_str::<Response<NoResult, NoData>>(
+        r#"{"jsonrpc":"2.0","result":1,"id":1}"#
+    )
+    .is_err());
+}
+
+#[test]
+#[cfg(test)]
+#[should_panic]
+fn server_error_out_of_range() {
+    let _ = ErrorCode::server_error(-31999);
 }
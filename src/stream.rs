@@ -0,0 +1,115 @@
+//! Streaming serialization of large results.
+
+use std::cell::Cell;
+use std::io::{Read, Write};
+
+use serde::{Serialize, Serializer};
+
+use crate::Id;
+
+/// Serializes the items of an iterator as a JSON array, without collecting them first.
+///
+/// Using this type as the result of a [`Response`] and serializing it with
+/// [`serde_json::to_writer`] streams the items into the output as they are produced, instead
+/// of requiring the whole result to be in memory.
+///
+/// The iterator is consumed by the first serialization. Serializing a [`SerializeIter`] a
+/// second time fails, so it must be serialized exactly once: it cannot go through functions
+/// that serialize their input before writing it, such as [`check_finite`] or the
+/// [canonical writers].
+///
+/// # Examples
+///
+/// ```
+/// use jsonrpc_sys::{Id, Response, SerializeIter};
+///
+/// let response = Response::<_, ()> {
+///     result: Ok(SerializeIter::new((0..3).map(|i| i * 2))),
+///     id: Id::Uint(1),
+/// };
+///
+/// let mut out = Vec::new();
+/// serde_json::to_writer(&mut out, &response).unwrap();
+/// assert_eq!(out, br#"{"jsonrpc":"2.0","result":[0,2,4],"id":1}"#);
+/// ```
+///
+/// [`Response`]: crate::Response
+/// [`check_finite`]: crate::check_finite
+/// [canonical writers]: crate::write_canonical_response
+pub struct SerializeIter<I>(Cell<Option<I>>);
+
+impl<I> SerializeIter<I> {
+    /// Creates a new [`SerializeIter`] from the provided iterator.
+    #[inline(always)]
+    pub fn new(iter: I) -> Self {
+        Self(Cell::new(Some(iter)))
+    }
+}
+
+impl<I> std::fmt::Debug for SerializeIter<I> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("SerializeIter").finish_non_exhaustive()
+    }
+}
+
+impl<I> Serialize for SerializeIter<I>
+where
+    I: IntoIterator,
+    I::Item: Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self.0.take() {
+            Some(iter) => serializer.collect_seq(iter),
+            None => Err(serde::ser::Error::custom(
+                "`SerializeIter` cannot be serialized more than once",
+            )),
+        }
+    }
+}
+
+/// Writes a successful JSON-RPC 2.0 response to the provided writer, copying its result from
+/// the provided reader.
+///
+/// The reader must produce a single valid JSON value, such as a pre-serialized state dump
+/// stored in a file. It is copied as-is, without being parsed or buffered entirely in memory.
+pub fn write_success_from_reader<W, R>(mut writer: W, mut result: R, id: Id) -> std::io::Result<()>
+where
+    W: Write,
+    R: Read,
+{
+    writer.write_all(br#"{"jsonrpc":"2.0","result":"#)?;
+    std::io::copy(&mut result, &mut writer)?;
+    writer.write_all(br#","id":"#)?;
+    serde_json::to_writer(&mut writer, &id)?;
+    writer.write_all(b"}")
+}
+
+#[test]
+#[cfg(test)]
+fn stream_from_reader() {
+    let mut out = Vec::new();
+    write_success_from_reader(&mut out, &b"{\"a\":[1,2]}"[..], Id::from("x")).unwrap();
+    let response: crate::Response<serde_json::Value, ()> = serde_json::from_slice(&out).unwrap();
+    assert_eq!(response.result.unwrap(), serde_json::json!({ "a": [1, 2] }));
+}
+
+#[test]
+#[cfg(test)]
+fn stream_request_params() {
+    let out = crate::write_request("m", SerializeIter::new(0..3), Id::Uint(1)).unwrap();
+    assert_eq!(
+        out,
+        br#"{"jsonrpc":"2.0","method":"m","params":[0,1,2],"id":1}"#
+    );
+}
+
+#[test]
+#[cfg(test)]
+fn serialize_iter_once() {
+    let params = SerializeIter::new(0..3);
+    crate::check_finite(&params).unwrap();
+    assert!(crate::write_request("m", &params, Id::Uint(1)).is_err());
+}
@@ -0,0 +1,229 @@
+//! Helpers for testing custom parameter, result and error types against the envelope types
+//! of this crate.
+
+use std::fmt::Debug;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::{Id, Request, Response};
+
+/// Serializes the provided request to JSON, deserializes it back and asserts that the result is
+/// equal to the original request.
+///
+/// # Panics
+///
+/// This function panics if the request cannot be serialized or deserialized, or if the
+/// deserialized request differs from the original one.
+#[track_caller]
+pub fn assert_roundtrip_request<P>(request: &Request<P>)
+where
+    P: Serialize + DeserializeOwned + PartialEq + Debug,
+{
+    let json = serde_json::to_string(request).expect("failed to serialize the request");
+    let back: Request<P> = serde_json::from_str(&json)
+        .unwrap_or_else(|err| panic!("failed to deserialize `{json}`: {err}"));
+
+    assert_eq!(request.method, back.method, "method mismatch in `{json}`");
+    assert_eq!(request.params, back.params, "params mismatch in `{json}`");
+    assert_eq!(
+        request.id.as_ref().map(id_value),
+        back.id.as_ref().map(id_value),
+        "id mismatch in `{json}`"
+    );
+}
+
+/// Serializes the provided response to JSON, deserializes it back and asserts that the result is
+/// equal to the original response.
+///
+/// # Panics
+///
+/// This function panics if the response cannot be serialized or deserialized, or if the
+/// deserialized response differs from the original one.
+#[track_caller]
+pub fn assert_roundtrip_response<T, E>(response: &Response<T, E>)
+where
+    T: Serialize + DeserializeOwned + PartialEq + Debug,
+    E: Serialize + DeserializeOwned + PartialEq + Debug,
+{
+    let json = serde_json::to_string(response).expect("failed to serialize the response");
+    let back: Response<T, E> = serde_json::from_str(&json)
+        .unwrap_or_else(|err| panic!("failed to deserialize `{json}`: {err}"));
+
+    match (&response.result, &back.result) {
+        (Ok(a), Ok(b)) => assert_eq!(a, b, "result mismatch in `{json}`"),
+        (Err(a), Err(b)) => {
+            assert_eq!(a.code, b.code, "error code mismatch in `{json}`");
+            assert_eq!(a.message, b.message, "error message mismatch in `{json}`");
+            assert_eq!(a.data, b.data, "error data mismatch in `{json}`");
+        }
+        _ => panic!("result/error mismatch in `{json}`"),
+    }
+    assert_eq!(
+        id_value(&response.id),
+        id_value(&back.id),
+        "id mismatch in `{json}`"
+    );
+}
+
+/// The kind of message of a [`TestVector`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MessageKind {
+    /// A single request.
+    Request,
+    /// A single response.
+    Response,
+    /// Either a single request or a batch of requests.
+    Batch,
+}
+
+/// A message of the conformance corpus returned by [`corpus`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TestVector {
+    /// A short, unique, name describing the vector.
+    pub name: &'static str,
+    /// The kind of message.
+    pub kind: MessageKind,
+    /// Whether the message is valid according to the specification.
+    pub valid: bool,
+    /// The message itself.
+    pub message: &'static str,
+}
+
+/// Returns the conformance corpus bundled with this crate.
+///
+/// The corpus is a collection of valid and invalid wire messages covering the edge cases of
+/// the specification (identifiers, batches, duplicate members, responses with both a result
+/// and an error, and so on). Servers and clients can feed them to their own stack to check
+/// that they accept and reject the same messages as the specification.
+///
+/// Note that this crate is more lenient than the specification for a few of these vectors:
+///
+/// - an empty batch (`[]`) can be deserialized as [`MaybeBatchedRequests::Batch`];
+/// - a response without an `id` member is deserialized with an [`Id::Null`] identifier;
+/// - a response with an `error` member and a `null` result is deserialized as an error, as
+///   some servers send both.
+///
+/// [`MaybeBatchedRequests::Batch`]: crate::MaybeBatchedRequests::Batch
+pub fn corpus() -> Vec<TestVector> {
+    const CORPUS: &str = include_str!("../corpus/vectors.txt");
+
+    CORPUS
+        .lines()
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| {
+            let mut parts = line.splitn(4, ' ');
+            let mut next = || parts.next().expect("malformed conformance vector");
+
+            let valid = match next() {
+                "valid" => true,
+                "invalid" => false,
+                other => panic!("invalid validity `{other}` in conformance vector"),
+            };
+            let kind = match next() {
+                "request" => MessageKind::Request,
+                "response" => MessageKind::Response,
+                "batch" => MessageKind::Batch,
+                other => panic!("invalid kind `{other}` in conformance vector"),
+            };
+
+            TestVector {
+                name: next(),
+                kind,
+                valid,
+                message: next(),
+            }
+        })
+        .collect()
+}
+
+/// Converts an [`Id`] to its JSON representation.
+///
+/// Identifiers are compared through their JSON representation because non-negative integers
+/// are always deserialized as [`Id::Uint`], even when they were created as [`Id::Int`].
+fn id_value(id: &Id) -> serde_json::Value {
+    serde_json::to_value(id).expect("failed to serialize the id")
+}
+
+/// Asserts that the provided JSON message is *not* a valid JSON-RPC 2.0 request with parameters
+/// of type `P`.
+///
+/// # Panics
+///
+/// This function panics if `json` is successfully deserialized as a [`Request`].
+#[track_caller]
+pub fn assert_invalid_request<P>(json: &str)
+where
+    P: DeserializeOwned + Debug,
+{
+    if let Ok(request) = serde_json::from_str::<Request<P>>(json) {
+        panic!("`{json}` was accepted as a valid request: {request:?}");
+    }
+}
+
+/// Asserts that the provided JSON message is *not* a valid JSON-RPC 2.0 response with result
+/// and error data of types `T` and `E`.
+///
+/// # Panics
+///
+/// This function panics if `json` is successfully deserialized as a [`Response`].
+#[track_caller]
+pub fn assert_invalid_response<T, E>(json: &str)
+where
+    T: DeserializeOwned + Debug,
+    E: DeserializeOwned + Debug,
+{
+    if let Ok(response) = serde_json::from_str::<Response<T, E>>(json) {
+        panic!("`{json}` was accepted as a valid response: {response:?}");
+    }
+}
+
+#[test]
+#[cfg(test)]
+fn roundtrip() {
+    use std::borrow::Cow;
+
+    assert_roundtrip_request(&Request {
+        method: Cow::Borrowed("add"),
+        params: (1, 2),
+        id: Some(Id::Int(1)),
+    });
+    assert_roundtrip_response::<_, ()>(&Response {
+        result: Ok(3),
+        id: Id::Int(1),
+    });
+    assert_invalid_request::<()>(r#"{"jsonrpc":"1.0","method":"add","params":null}"#);
+    assert_invalid_response::<i32, ()>(r#"{"jsonrpc":"2.0","id":1}"#);
+}
+
+#[test]
+#[cfg(test)]
+fn conformance() {
+    use serde_json::Value;
+
+    use crate::MaybeBatchedRequests;
+
+    // Invalid vectors that this crate deliberately accepts (see the documentation of `corpus`).
+    const ACCEPTED: &[&str] = &["missing-id", "null-result-with-error"];
+
+    for vector in corpus() {
+        let accepted = match vector.kind {
+            MessageKind::Request => {
+                serde_json::from_str::<Request<Option<Value>>>(vector.message).is_ok()
+            }
+            MessageKind::Response => {
+                serde_json::from_str::<Response<Value, Value>>(vector.message).is_ok()
+            }
+            MessageKind::Batch => {
+                match serde_json::from_str::<MaybeBatchedRequests<Option<Value>>>(vector.message) {
+                    Ok(MaybeBatchedRequests::Batch(batch)) => !batch.is_empty(),
+                    Ok(MaybeBatchedRequests::Single(_)) => true,
+                    Err(_) => false,
+                }
+            }
+        };
+
+        let expected = vector.valid || ACCEPTED.contains(&vector.name);
+        assert_eq!(accepted, expected, "conformance vector `{}`", vector.name);
+    }
+}
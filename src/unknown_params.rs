@@ -1,4 +1,30 @@
+use std::collections::HashMap;
+
 use serde::{Deserialize, Serialize};
+use serde_json::value::RawValue;
+
+use crate::{Error, Request, Response, RpcResult};
+
+/// A request whose parameters are kept as raw JSON.
+///
+/// The parameters are borrowed from the input without being parsed, and written back
+/// untouched when the request is serialized. This allows proxies to rewrite the envelope
+/// (such as the ID of the request) without paying for a full re-serialization of the
+/// parameters.
+///
+/// The envelope itself is re-serialized: its members are written in the standard order, and
+/// unknown members are dropped. Missing parameters are read as `None`, which is written back
+/// as `"params": null`. The specification does not allow this, so proxies forwarding requests
+/// without parameters to strict peers should check for `None` and write those themselves.
+pub type RawRequest<'a> = Request<'a, Option<&'a RawValue>>;
+
+/// A response whose result and error data are kept as raw JSON.
+///
+/// See [`RawRequest`] for more information. A `null` result is represented by `Ok(None)`.
+///
+/// The error data is kept as-is when present, but an explicit `"data": null` is read like a
+/// missing `data` member, and is therefore omitted when the response is written back.
+pub type RawResponse<'a> = Response<'a, Option<&'a RawValue>, &'a RawValue>;
 
 /// The parameters passed to a JSON-RPC 2.0 request.
 ///
@@ -6,7 +32,7 @@ use serde::{Deserialize, Serialize};
 /// them to check the name of the method before calling [`UnknownParams::parse`] to deserialize
 /// the corresponding parameters without having to parse the request a second time.
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct UnknownParams<'a>(#[serde(borrow)] Option<&'a serde_json::value::RawValue>);
+pub struct UnknownParams<'a>(#[serde(borrow)] Option<&'a RawValue>);
 
 impl<'a> UnknownParams<'a> {
     /// Parses the parameters as a JSON value.
@@ -14,7 +40,125 @@ impl<'a> UnknownParams<'a> {
     where
         T: Deserialize<'a>,
     {
-        let s = self.0.map_or("[]", serde_json::value::RawValue::get);
+        let s = self.0.map_or("[]", RawValue::get);
         serde_json::from_str(s)
     }
+
+    /// Parses the parameters, returning an [`ErrorCode::INVALID_PARAMS`] error on failure.
+    ///
+    /// Positional parameters can be taken as a tuple, which must have exactly as many elements
+    /// as there are parameters.
+    ///
+    /// [`ErrorCode::INVALID_PARAMS`]: crate::ErrorCode::INVALID_PARAMS
+    pub fn take<T>(&self) -> RpcResult<T>
+    where
+        T: Deserialize<'a>,
+    {
+        self.parse().map_err(Error::invalid_params)
+    }
+
+    /// Parses the positional parameter at the provided index.
+    ///
+    /// Returns `None` when the parameter is missing or `null`, and an
+    /// [`ErrorCode::INVALID_PARAMS`] error when the parameters are not an array or the
+    /// parameter is not valid.
+    ///
+    /// [`ErrorCode::INVALID_PARAMS`]: crate::ErrorCode::INVALID_PARAMS
+    pub fn optional<T>(&self, index: usize) -> RpcResult<Option<T>>
+    where
+        T: Deserialize<'a>,
+    {
+        let params: Vec<&'a RawValue> = self.take()?;
+        match params.get(index) {
+            Some(param) => serde_json::from_str(param.get()).map_err(Error::invalid_params),
+            None => Ok(None),
+        }
+    }
+
+    /// Parses the named parameter with the provided key.
+    ///
+    /// Returns an [`ErrorCode::INVALID_PARAMS`] error when the parameters are not an object, or
+    /// when the parameter is missing or not valid. Optional parameters can be taken as an
+    /// `Option<T>`.
+    ///
+    /// [`ErrorCode::INVALID_PARAMS`]: crate::ErrorCode::INVALID_PARAMS
+    pub fn named<T>(&self, key: &str) -> RpcResult<T>
+    where
+        T: Deserialize<'a>,
+    {
+        let s = self.0.map_or("{}", RawValue::get);
+        let params: HashMap<String, &'a RawValue> =
+            serde_json::from_str(s).map_err(Error::invalid_params)?;
+        let param = params.get(key).map_or("null", |param| param.get());
+        serde_json::from_str(param).map_err(|error| {
+            Error::invalid_params(serde::de::Error::custom(format_args!(
+                "invalid parameter `{key}`: {error}"
+            )))
+        })
+    }
+}
+
+#[test]
+#[cfg(test)]
+fn raw_passthrough() {
+    let input = r#"{"jsonrpc":"2.0","method":"m","params":[1.0e3, {"b" : 1}],"id":1}"#;
+    let mut request: RawRequest = serde_json::from_str(input).unwrap();
+    request.id = Some(crate::Id::Uint(2));
+    assert_eq!(
+        serde_json::to_string(&request).unwrap(),
+        r#"{"jsonrpc":"2.0","method":"m","params":[1.0e3, {"b" : 1}],"id":2}"#
+    );
+
+    let input = r#"{"jsonrpc":"2.0","result":null,"id":1}"#;
+    let response: RawResponse = serde_json::from_str(input).unwrap();
+    assert_eq!(serde_json::to_string(&response).unwrap(), input);
+
+    let input = r#"{"jsonrpc":"2.0","error":{"code":1,"message":"","data":{ }},"id":1}"#;
+    let response: RawResponse = serde_json::from_str(input).unwrap();
+    assert_eq!(serde_json::to_string(&response).unwrap(), input);
+}
+
+#[test]
+#[cfg(test)]
+fn extraction() {
+    use crate::ErrorCode;
+
+    let request: Request<UnknownParams> =
+        serde_json::from_str(r#"{"jsonrpc":"2.0","method":"m","params":[1,"a",null]}"#).unwrap();
+    let (a, b, c): (u32, &str, Option<u32>) = request.params.take().unwrap();
+    assert_eq!((a, b, c), (1, "a", None));
+    assert_eq!(request.params.optional::<u32>(0).unwrap(), Some(1));
+    assert_eq!(request.params.optional::<u32>(2).unwrap(), None);
+    assert_eq!(request.params.optional::<u32>(3).unwrap(), None);
+    let error = request.params.optional::<u32>(1).unwrap_err();
+    assert_eq!(error.code, ErrorCode::INVALID_PARAMS);
+    let error = request.params.take::<(u32, &str)>().unwrap_err();
+    assert_eq!(error.code, ErrorCode::INVALID_PARAMS);
+
+    let request: Request<UnknownParams> =
+        serde_json::from_str(r#"{"jsonrpc":"2.0","method":"m","params":{"a":1}}"#).unwrap();
+    assert_eq!(request.params.named::<u32>("a").unwrap(), 1);
+    assert_eq!(request.params.named::<Option<u32>>("b").unwrap(), None);
+    let error = request.params.named::<u32>("b").unwrap_err();
+    assert_eq!(error.code, ErrorCode::INVALID_PARAMS);
+    assert!(request.params.optional::<u32>(0).is_err());
+}
+
+#[test]
+#[cfg(test)]
+fn raw_missing_members() {
+    let input = r#"{"jsonrpc":"2.0","method":"m"}"#;
+    let request: RawRequest = serde_json::from_str(input).unwrap();
+    assert!(request.params.is_none());
+    assert_eq!(
+        serde_json::to_string(&request).unwrap(),
+        r#"{"jsonrpc":"2.0","method":"m","params":null}"#
+    );
+
+    let input = r#"{"jsonrpc":"2.0","error":{"code":1,"message":"","data":null},"id":1}"#;
+    let response: RawResponse = serde_json::from_str(input).unwrap();
+    assert_eq!(
+        serde_json::to_string(&response).unwrap(),
+        r#"{"jsonrpc":"2.0","error":{"code":1,"message":""},"id":1}"#
+    );
 }
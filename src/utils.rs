@@ -2,19 +2,19 @@
 
 use std::borrow::Cow;
 
-use crate::{Error, ErrorCode, Id, Request, Response};
-
-/// A type that cannot be serialized.
-enum CantSerialize {}
-
-impl serde::Serialize for CantSerialize {
-    #[inline(always)]
-    fn serialize<S>(&self, _: S) -> Result<S::Ok, S::Error>
-    where
-        S: serde::Serializer,
-    {
-        match *self {}
-    }
+use crate::{Error, ErrorCode, Id, MaybeBatchedRequests, NoData, NoResult, Request, Response};
+
+/// Serializes the provided value to a vector of bytes.
+///
+/// `size_hint` is the size of the envelope of the message, which excludes its payload. The
+/// buffer is allocated with that capacity up front, and grows as needed for the payload.
+fn to_vec_with_capacity<T>(value: &T, size_hint: usize) -> serde_json::Result<Vec<u8>>
+where
+    T: ?Sized + serde::Serialize,
+{
+    let mut buf = Vec::with_capacity(size_hint);
+    serde_json::to_writer(&mut buf, value)?;
+    Ok(buf)
 }
 
 /// Writes a JSON-RPC 2.0 request with the provided method, params and ID to a vector of bytes.
@@ -31,7 +31,7 @@ where
         params,
         id: id.into(),
     };
-    serde_json::to_vec(&request)
+    to_vec_with_capacity(&request, request.size_hint())
 }
 
 /// Writes a JSON-RPC 2.0 response with the provided result and ID to a vector of bytes.
@@ -41,7 +41,7 @@ where
     E: serde::Serialize,
 {
     let response = Response { result, id };
-    serde_json::to_vec(&response)
+    to_vec_with_capacity(&response, response.size_hint())
 }
 
 /// Writes a successful JSON-RPC 2.0 response with the provided result and ID to a vector of bytes.
@@ -49,11 +49,11 @@ pub fn write_success<T>(value: T, id: Id) -> serde_json::Result<Vec<u8>>
 where
     T: serde::Serialize,
 {
-    let response = Response::<T, CantSerialize> {
+    let response = Response::<T, NoData> {
         result: Ok(value),
         id,
     };
-    serde_json::to_vec(&response)
+    to_vec_with_capacity(&response, response.size_hint())
 }
 
 /// Writes a failed JSON-RPC 2.0 response with the provided error and ID to a vector of bytes.
@@ -66,7 +66,7 @@ pub fn write_failure<E>(
 where
     E: serde::Serialize,
 {
-    let response = Response::<CantSerialize, E> {
+    let response = Response::<NoResult, E> {
         result: Err(Error {
             code: code.into(),
             message: Cow::Borrowed(message),
@@ -74,16 +74,19 @@ where
         }),
         id,
     };
-    serde_json::to_vec(&response)
+    to_vec_with_capacity(&response, response.size_hint())
 }
 
 /// Writes a failed JSON-RPC 2.0 response with the provided error and ID to a vector of bytes.
+///
+/// The `data` member is omitted. Use [`write_failure`] with `()` as the data to write an
+/// explicit `"data": null` instead.
 pub fn write_datalass_failure(
     code: impl Into<ErrorCode>,
     message: &str,
     id: Id,
 ) -> serde_json::Result<Vec<u8>> {
-    let response = Response::<CantSerialize, CantSerialize> {
+    let response = Response::<NoResult, NoData> {
         result: Err(Error {
             code: code.into(),
             message: Cow::Borrowed(message),
@@ -91,7 +94,81 @@ pub fn write_datalass_failure(
         }),
         id,
     };
-    serde_json::to_vec(&response)
+    to_vec_with_capacity(&response, response.size_hint())
+}
+
+/// Writes the failed JSON-RPC 2.0 response corresponding to an error returned by
+/// [`read_request`] for the provided bytes to a vector of bytes.
+///
+/// The error code and the ID of the response are chosen by [`classify_read_error`]: requests
+/// whose parameters are invalid are answered with [`ErrorCode::INVALID_PARAMS`] and their own
+/// ID, and requests that could not be read with a `null` ID. Notifications must not be
+/// answered, so `None` is returned for notifications whose parameters are invalid.
+pub fn write_read_error(
+    bytes: &[u8],
+    error: &serde_json::Error,
+) -> serde_json::Result<Option<Vec<u8>>> {
+    let (code, id) = classify_read_error(bytes, error);
+    let Some(id) = id else {
+        return Ok(None);
+    };
+
+    let response = Response::<NoResult, NoData> {
+        result: Err(Error {
+            code,
+            ..Error::from_read_error(error)
+        }),
+        id,
+    };
+    to_vec_with_capacity(&response, response.size_hint()).map(Some)
+}
+
+/// Classifies an error returned by [`read_request`] for the provided bytes.
+///
+/// When the request itself was valid but its parameters could not be deserialized, this
+/// returns [`ErrorCode::INVALID_PARAMS`] along with the ID of the request, which is `None` for
+/// notifications. Otherwise, the code is chosen by [`Error::from_read_error`] and the ID is
+/// [`Id::Null`], as required by the specification for requests that could not be read.
+///
+/// The bytes are only read a second time here, when an error has already occurred.
+pub fn classify_read_error<'a>(
+    bytes: &'a [u8],
+    error: &serde_json::Error,
+) -> (ErrorCode, Option<Id<'a>>) {
+    match read_request::<Option<serde::de::IgnoredAny>>(bytes) {
+        Ok(request) => (ErrorCode::INVALID_PARAMS, request.id),
+        Err(_) => (envelope_error_code(error), Some(Id::Null)),
+    }
+}
+
+/// Returns the error code for an error that occurred while reading the envelope of a request.
+fn envelope_error_code(error: &serde_json::Error) -> ErrorCode {
+    use serde_json::error::Category;
+
+    match error.classify() {
+        Category::Syntax | Category::Eof => ErrorCode::PARSE_ERROR,
+        Category::Data => ErrorCode::INVALID_REQUEST,
+        Category::Io => ErrorCode::INTERNAL_ERROR,
+    }
+}
+
+impl<E> Error<'static, E> {
+    /// Creates an error from an error that occurred while reading a request.
+    ///
+    /// Syntax errors and unexpected ends of input map to [`ErrorCode::PARSE_ERROR`], input
+    /// that was valid JSON but not a valid request maps to [`ErrorCode::INVALID_REQUEST`], and
+    /// I/O errors map to [`ErrorCode::INTERNAL_ERROR`].
+    ///
+    /// The error alone does not tell whether the envelope of the request or its parameters
+    /// were invalid, so this is only correct for envelope errors. Use [`classify_read_error`]
+    /// to answer requests with invalid parameters with [`ErrorCode::INVALID_PARAMS`].
+    pub fn from_read_error(error: &serde_json::Error) -> Self {
+        Self {
+            code: envelope_error_code(error),
+            message: Cow::Owned(error.to_string()),
+            data: None,
+        }
+    }
 }
 
 /// Attmepts to read a request from a slice of bytes.
@@ -103,7 +180,7 @@ where
 }
 
 /// Read a response from a slice of bytes.
-pub fn read_response<'a, T, E>(bytes: &'a [u8]) -> serde_json::Result<Response<T, E>>
+pub fn read_response<'a, T, E>(bytes: &'a [u8]) -> serde_json::Result<Response<'a, T, E>>
 where
     T: serde::Deserialize<'a>,
     E: serde::Deserialize<'a>,
@@ -114,9 +191,477 @@ where
 /// Read a response from a slice of bytes, ignoring any potential error data.
 pub fn read_response_ignore_error<'a, T>(
     bytes: &'a [u8],
-) -> serde_json::Result<Response<T, serde::de::IgnoredAny>>
+) -> serde_json::Result<Response<'a, T, serde::de::IgnoredAny>>
 where
     T: serde::Deserialize<'a>,
 {
     serde_json::from_slice(bytes)
 }
+
+/// Reads a request from a JSON5 string.
+///
+/// JSON5 is a superset of JSON accepting comments, trailing commas, unquoted keys and a few
+/// other conveniences. This is useful for hand-written fixtures and developer tools, but
+/// should not be used to read messages sent by other peers. The writers of this module always
+/// emit strict JSON.
+#[cfg(feature = "json5")]
+pub fn read_request_json5<'a, T>(s: &'a str) -> Result<Request<'a, T>, json5::Error>
+where
+    T: serde::de::Deserialize<'a>,
+{
+    json5::from_str(s)
+}
+
+/// Reads a response from a JSON5 string.
+///
+/// See [`read_request_json5`] for more information.
+#[cfg(feature = "json5")]
+pub fn read_response_json5<'a, T, E>(s: &'a str) -> Result<Response<'a, T, E>, json5::Error>
+where
+    T: serde::Deserialize<'a>,
+    E: serde::Deserialize<'a>,
+{
+    json5::from_str(s)
+}
+
+/// Reads the non-standard members of the `error` object of a response from a slice of bytes.
+///
+/// Some servers include members other than `code`, `message` and `data` in their errors (such as
+/// `name` or `stack`). Those are ignored by [`read_response`], but proxies and debugging clients
+/// can use this function to capture them, and [`write_response_with_error_members`] to write
+/// them back.
+///
+/// Returns `None` if the response has no `error` object.
+pub fn read_error_members(
+    bytes: &[u8],
+) -> serde_json::Result<Option<serde_json::Map<String, serde_json::Value>>> {
+    #[derive(serde::Deserialize)]
+    struct Envelope {
+        #[serde(default)]
+        error: Option<Members>,
+    }
+
+    #[derive(serde::Deserialize)]
+    struct Members {
+        #[serde(flatten)]
+        members: serde_json::Map<String, serde_json::Value>,
+    }
+
+    let envelope: Envelope = serde_json::from_slice(bytes)?;
+    Ok(envelope.error.map(|error| {
+        let mut members = error.members;
+        members.remove("code");
+        members.remove("message");
+        members.remove("data");
+        members
+    }))
+}
+
+/// Writes a JSON-RPC 2.0 response to a vector of bytes, adding the provided members to its
+/// `error` object.
+///
+/// Members named `code`, `message` or `data` are ignored, as are all members if the response
+/// is successful. See [`read_error_members`].
+pub fn write_response_with_error_members<T, E>(
+    response: &Response<T, E>,
+    members: &serde_json::Map<String, serde_json::Value>,
+) -> serde_json::Result<Vec<u8>>
+where
+    T: serde::Serialize,
+    E: serde::Serialize,
+{
+    #[derive(serde::Serialize)]
+    struct OutgoingResponse<'a, T, E> {
+        jsonrpc: &'a str,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        result: Option<&'a T>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        error: Option<OutgoingError<'a, E>>,
+        id: &'a Id<'a>,
+    }
+
+    #[derive(serde::Serialize)]
+    struct OutgoingError<'a, E> {
+        code: i64,
+        message: &'a str,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        data: Option<&'a E>,
+        #[serde(flatten)]
+        members: ExtraMembers<'a>,
+    }
+
+    /// The members to add to the error, without the ones defined by the specification.
+    struct ExtraMembers<'a>(&'a serde_json::Map<String, serde_json::Value>);
+
+    impl serde::Serialize for ExtraMembers<'_> {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: serde::Serializer,
+        {
+            serializer.collect_map(
+                self.0
+                    .iter()
+                    .filter(|(key, _)| !matches!(key.as_str(), "code" | "message" | "data")),
+            )
+        }
+    }
+
+    let (result, error) = match response.result {
+        Ok(ref result) => (Some(result), None),
+        Err(ref error) => (
+            None,
+            Some(OutgoingError {
+                code: error.code.0,
+                message: &error.message,
+                data: error.data.as_ref(),
+                members: ExtraMembers(members),
+            }),
+        ),
+    };
+
+    let size_hint = response.size_hint();
+    let response = OutgoingResponse {
+        jsonrpc: "2.0",
+        result,
+        error,
+        id: &response.id,
+    };
+    to_vec_with_capacity(&response, size_hint)
+}
+
+/// Options for interoperating with peers that expect a non-standard `jsonrpc` member.
+///
+/// The default options produce the same output as the other writers of this module: the
+/// `jsonrpc` member is the string `"2.0"`, and comes first.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct WriteOptions {
+    /// Whether to write the version as the number `2.0` instead of the string `"2.0"`.
+    pub numeric_version: bool,
+    /// Whether to write the `jsonrpc` member last instead of first.
+    pub version_last: bool,
+}
+
+impl WriteOptions {
+    /// Rewrites the `jsonrpc` member of a serialized message according to these options.
+    fn apply(&self, mut buf: Vec<u8>) -> Vec<u8> {
+        // Messages are always serialized with this prefix, as `jsonrpc` is their first member
+        // and they are never empty.
+        const PREFIX: &[u8] = br#"{"jsonrpc":"2.0","#;
+        debug_assert!(buf.starts_with(PREFIX));
+
+        let version: &[u8] = if self.numeric_version {
+            br#""jsonrpc":2.0"#
+        } else {
+            br#""jsonrpc":"2.0""#
+        };
+
+        if self.version_last {
+            buf.drain(1..PREFIX.len());
+            buf.pop();
+            buf.push(b',');
+            buf.extend_from_slice(version);
+            buf.push(b'}');
+        } else if self.numeric_version {
+            buf.splice(1..PREFIX.len() - 1, version.iter().copied());
+        }
+
+        buf
+    }
+}
+
+/// Writes a JSON-RPC 2.0 request to a vector of bytes, using the provided [`WriteOptions`].
+pub fn write_request_with_options<P>(
+    request: &Request<P>,
+    options: WriteOptions,
+) -> serde_json::Result<Vec<u8>>
+where
+    P: serde::Serialize,
+{
+    to_vec_with_capacity(request, request.size_hint()).map(|buf| options.apply(buf))
+}
+
+/// Writes a JSON-RPC 2.0 response to a vector of bytes, using the provided [`WriteOptions`].
+pub fn write_response_with_options<T, E>(
+    response: &Response<T, E>,
+    options: WriteOptions,
+) -> serde_json::Result<Vec<u8>>
+where
+    T: serde::Serialize,
+    E: serde::Serialize,
+{
+    to_vec_with_capacity(response, response.size_hint()).map(|buf| options.apply(buf))
+}
+
+impl<'a, P> TryFrom<&'a [u8]> for Request<'a, P>
+where
+    P: serde::Deserialize<'a>,
+{
+    type Error = serde_json::Error;
+
+    #[inline]
+    fn try_from(bytes: &'a [u8]) -> serde_json::Result<Self> {
+        read_request(bytes)
+    }
+}
+
+impl<P> std::str::FromStr for Request<'static, P>
+where
+    P: serde::de::DeserializeOwned,
+{
+    type Err = serde_json::Error;
+
+    fn from_str(s: &str) -> serde_json::Result<Self> {
+        read_request(s.as_bytes()).map(Request::into_owned)
+    }
+}
+
+impl<'a, T, E> TryFrom<&'a [u8]> for Response<'a, T, E>
+where
+    T: serde::Deserialize<'a>,
+    E: serde::Deserialize<'a>,
+{
+    type Error = serde_json::Error;
+
+    #[inline]
+    fn try_from(bytes: &'a [u8]) -> serde_json::Result<Self> {
+        read_response(bytes)
+    }
+}
+
+impl<T, E> std::str::FromStr for Response<'static, T, E>
+where
+    T: serde::de::DeserializeOwned,
+    E: serde::de::DeserializeOwned,
+{
+    type Err = serde_json::Error;
+
+    fn from_str(s: &str) -> serde_json::Result<Self> {
+        read_response(s.as_bytes()).map(Response::into_owned)
+    }
+}
+
+impl<'a, P> TryFrom<&'a [u8]> for MaybeBatchedRequests<'a, P>
+where
+    P: serde::Deserialize<'a>,
+{
+    type Error = serde_json::Error;
+
+    #[inline]
+    fn try_from(bytes: &'a [u8]) -> serde_json::Result<Self> {
+        serde_json::from_slice(bytes)
+    }
+}
+
+impl<P> std::str::FromStr for MaybeBatchedRequests<'static, P>
+where
+    P: serde::de::DeserializeOwned,
+{
+    type Err = serde_json::Error;
+
+    fn from_str(s: &str) -> serde_json::Result<Self> {
+        serde_json::from_str::<MaybeBatchedRequests<P>>(s).map(MaybeBatchedRequests::into_owned)
+    }
+}
+
+#[test]
+#[cfg(test)]
+fn error_members_roundtrip() {
+    let input = br#"{"jsonrpc":"2.0","error":{"code":1,"message":"","stack":"at foo"},"id":1}"#;
+    let members = read_error_members(input).unwrap().unwrap();
+    assert_eq!(members.len(), 1);
+
+    let response = read_response::<(), ()>(input).unwrap();
+    let output = write_response_with_error_members(&response, &members).unwrap();
+    assert_eq!(output, input);
+
+    let response = read_response::<u32, ()>(br#"{"jsonrpc":"2.0","result":1,"id":1}"#).unwrap();
+    let output = write_response_with_error_members(&response, &members).unwrap();
+    assert_eq!(output, br#"{"jsonrpc":"2.0","result":1,"id":1}"#);
+}
+
+#[test]
+#[cfg(test)]
+fn read_error_reply() {
+    let error = read_request::<()>(b"{").unwrap_err();
+    let reply = write_read_error(b"{", &error).unwrap().unwrap();
+    let reply = read_response_ignore_error::<()>(&reply).unwrap();
+    assert_eq!(reply.id, Id::Null);
+    assert_eq!(reply.result.unwrap_err().code, ErrorCode::PARSE_ERROR);
+
+    let input = br#"{"jsonrpc":"2.0","method":"m","params":["x"],"id":7}"#;
+    let error = read_request::<(i32,)>(input).unwrap_err();
+    let reply = write_read_error(input, &error).unwrap().unwrap();
+    let reply = read_response_ignore_error::<()>(&reply).unwrap();
+    assert_eq!(reply.id, Id::Uint(7));
+    assert_eq!(reply.result.unwrap_err().code, ErrorCode::INVALID_PARAMS);
+
+    let input = br#"{"jsonrpc":"2.0","method":"m","params":["x"]}"#;
+    let error = read_request::<(i32,)>(input).unwrap_err();
+    assert!(write_read_error(input, &error).unwrap().is_none());
+}
+
+#[test]
+#[cfg(all(test, feature = "json5"))]
+fn json5_comments_and_trailing_commas() {
+    let request = r#"{
+        // A comment.
+        jsonrpc: "2.0",
+        method: "add",
+        params: [1, 2,],
+        id: 1,
+    }"#;
+    let request = read_request_json5::<(i32, i32)>(request).unwrap();
+    assert_eq!(request.method, "add");
+    assert_eq!(request.params, (1, 2));
+    assert_eq!(request.id, Some(Id::Uint(1)));
+}
+
+#[test]
+#[cfg(test)]
+fn conversions() {
+    let bytes = br#"{"jsonrpc":"2.0","method":"add","params":[1,2],"id":"a"}"#;
+    let request = Request::<[u32; 2]>::try_from(&bytes[..]).unwrap();
+    assert_eq!(request.params, [1, 2]);
+
+    let request: Request<'static, [u32; 2]> = std::str::from_utf8(bytes).unwrap().parse().unwrap();
+    assert_eq!(request.id, Some(Id::Str(Cow::Borrowed("a"))));
+
+    let response: Response<'static, u32, ()> =
+        r#"{"jsonrpc":"2.0","result":3,"id":"a"}"#.parse().unwrap();
+    assert_eq!(response.result.unwrap(), 3);
+
+    let batch: MaybeBatchedRequests<'static, [u32; 2]> =
+        r#"[{"jsonrpc":"2.0","method":"add","params":[1,2]}]"#
+            .parse()
+            .unwrap();
+    assert!(matches!(batch, MaybeBatchedRequests::Batch(ref b) if b.len() == 1));
+
+    assert!("{}".parse::<Request<'static, ()>>().is_err());
+}
+
+#[test]
+#[cfg(test)]
+fn write_options() {
+    let request = Request {
+        method: Cow::Borrowed("m"),
+        params: [1],
+        id: Some(Id::Uint(1)),
+    };
+    let response = Response::<u32, ()> {
+        result: Ok(1),
+        id: Id::Uint(1),
+    };
+
+    let write = |options| {
+        let request = write_request_with_options(&request, options).unwrap();
+        let response = write_response_with_options(&response, options).unwrap();
+        (
+            String::from_utf8(request).unwrap(),
+            String::from_utf8(response).unwrap(),
+        )
+    };
+
+    assert_eq!(
+        write(WriteOptions::default()),
+        (
+            r#"{"jsonrpc":"2.0","method":"m","params":[1],"id":1}"#.to_owned(),
+            r#"{"jsonrpc":"2.0","result":1,"id":1}"#.to_owned(),
+        )
+    );
+    assert_eq!(
+        write(WriteOptions {
+            numeric_version: true,
+            version_last: false,
+        }),
+        (
+            r#"{"jsonrpc":2.0,"method":"m","params":[1],"id":1}"#.to_owned(),
+            r#"{"jsonrpc":2.0,"result":1,"id":1}"#.to_owned(),
+        )
+    );
+    assert_eq!(
+        write(WriteOptions {
+            numeric_version: true,
+            version_last: true,
+        }),
+        (
+            r#"{"method":"m","params":[1],"id":1,"jsonrpc":2.0}"#.to_owned(),
+            r#"{"result":1,"id":1,"jsonrpc":2.0}"#.to_owned(),
+        )
+    );
+    assert_eq!(
+        write(WriteOptions {
+            numeric_version: false,
+            version_last: true,
+        })
+        .1,
+        r#"{"result":1,"id":1,"jsonrpc":"2.0"}"#
+    );
+}
+
+#[test]
+#[cfg(test)]
+fn classify_params_errors() {
+    let input = br#"{"jsonrpc":"2.0","method":"m","params":["x"],"id":7}"#;
+    let error = read_request::<(i32,)>(input).unwrap_err();
+    assert_eq!(
+        classify_read_error(input, &error),
+        (ErrorCode::INVALID_PARAMS, Some(Id::Uint(7)))
+    );
+
+    let input = br#"{"jsonrpc":"2.0","method":"m"}"#;
+    let error = read_request::<(i32,)>(input).unwrap_err();
+    assert_eq!(
+        classify_read_error(input, &error),
+        (ErrorCode::INVALID_PARAMS, None)
+    );
+
+    let input = br#"{"jsonrpc":"2.0","params":[1],"id":7}"#;
+    let error = read_request::<(i32,)>(input).unwrap_err();
+    assert_eq!(
+        classify_read_error(input, &error),
+        (ErrorCode::INVALID_REQUEST, Some(Id::Null))
+    );
+
+    let input = br#"{"jsonrpc":"2.0","method":"m","params":[1,"#;
+    let error = read_request::<(i32,)>(input).unwrap_err();
+    assert_eq!(
+        classify_read_error(input, &error),
+        (ErrorCode::PARSE_ERROR, Some(Id::Null))
+    );
+}
+
+#[test]
+#[cfg(test)]
+fn writer_capacity() {
+    let request = Request {
+        method: Cow::Borrowed("subtract"),
+        params: [42, 23],
+        id: Some(Id::Uint(1)),
+    };
+    let buf = write_request(&request.method, request.params, Id::Uint(1)).unwrap();
+    assert!(buf.capacity() >= request.size_hint());
+    assert!(buf.capacity() >= buf.len());
+
+    let options = WriteOptions {
+        numeric_version: true,
+        version_last: true,
+    };
+    let buf = write_request_with_options(&request, options).unwrap();
+    assert!(buf.capacity() >= request.size_hint());
+    assert!(buf.capacity() >= buf.len());
+}
+
+#[test]
+#[cfg(test)]
+fn classify_json_errors() {
+    let parse = serde_json::from_str::<crate::Request<()>>("{").unwrap_err();
+    let invalid = serde_json::from_str::<crate::Request<()>>("{}").unwrap_err();
+    assert_eq!(
+        Error::<()>::from_read_error(&parse).code,
+        ErrorCode::PARSE_ERROR
+    );
+    assert_eq!(
+        Error::<()>::from_read_error(&invalid).code,
+        ErrorCode::INVALID_REQUEST
+    );
+}